@@ -0,0 +1,106 @@
+/// How the values of a boolean field are written and read.
+///
+/// The first value of each set is the one written, and any value of a set is accepted when
+/// reading. Values are compared after trimming the field, so a blank field is read as the empty
+/// value `""`. Values that are in neither set fail to deserialize.
+///
+/// Without a mapping, a field is read as `false` if it is blank or `0` and as `true` otherwise,
+/// and is written as `1` or `0`.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{Booleans, FieldSet};
+///
+/// let fields = FieldSet::Seq(vec![
+///     FieldSet::new_field(0..1).booleans(Booleans::yes_no().or_true("y").or_false("n")),
+///     FieldSet::new_field(1..2).booleans(Booleans::flag("X")),
+/// ]);
+///
+/// let flags: (bool, bool) = fixed_width::from_str_with_fields("n ", fields.clone()).unwrap();
+/// assert_eq!(flags, (false, false));
+///
+/// let flags: Result<(bool, bool), _> = fixed_width::from_str_with_fields("?X", fields);
+/// assert!(flags.is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize)
+)]
+pub struct Booleans {
+    /// The values read as `true`. The first one is written for `true`.
+    pub true_values: Vec<String>,
+    /// The values read as `false`. The first one is written for `false`.
+    pub false_values: Vec<String>,
+}
+
+impl Booleans {
+    /// Creates a mapping that writes `t` for `true` and `f` for `false`, and only reads those
+    /// values back.
+    pub fn new<T: Into<String>, F: Into<String>>(t: T, f: F) -> Self {
+        Self {
+            true_values: vec![t.into()],
+            false_values: vec![f.into()],
+        }
+    }
+
+    /// `Y` for `true` and `N` for `false`.
+    pub fn yes_no() -> Self {
+        Self::new("Y", "N")
+    }
+
+    /// `T` for `true` and `F` for `false`.
+    pub fn true_false() -> Self {
+        Self::new("T", "F")
+    }
+
+    /// `1` for `true` and `0` for `false`.
+    pub fn one_zero() -> Self {
+        Self::new("1", "0")
+    }
+
+    /// The given flag for `true`, and a blank field for `false`.
+    pub fn flag<T: Into<String>>(t: T) -> Self {
+        Self::new(t, "")
+    }
+
+    /// Also reads the given value as `true`.
+    pub fn or_true<T: Into<String>>(mut self, val: T) -> Self {
+        self.true_values.push(val.into());
+        self
+    }
+
+    /// Also reads the given value as `false`.
+    pub fn or_false<T: Into<String>>(mut self, val: T) -> Self {
+        self.false_values.push(val.into());
+        self
+    }
+
+    /// Reads a blank field as the given value, instead of failing.
+    pub fn blank(self, val: bool) -> Self {
+        match val {
+            true => self.or_true(""),
+            false => self.or_false(""),
+        }
+    }
+
+    pub(crate) fn read(&self, text: &str) -> Option<bool> {
+        if self.true_values.iter().any(|v| v.trim() == text) {
+            Some(true)
+        } else if self.false_values.iter().any(|v| v.trim() == text) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn write(&self, val: bool) -> &str {
+        let values = match val {
+            true => &self.true_values,
+            false => &self.false_values,
+        };
+
+        values.first().map_or("", String::as_str)
+    }
+}
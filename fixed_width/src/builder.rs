@@ -0,0 +1,167 @@
+use crate::FieldSet;
+
+/// Builds a `FieldSet` from field widths, assigning each field the byte range that follows the
+/// previous one. Created with `FieldSet::builder`.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{FieldSet, Justify};
+///
+/// let fields = FieldSet::builder()
+///     .field("name", 6)
+///     .field_with("age", 3, |f| f.pad_with('0').justify(Justify::Right))
+///     .filler(2)
+///     .field("height", 4)
+///     .build();
+///
+/// // Is identical to:
+/// let by_range = FieldSet::Seq(vec![
+///     FieldSet::new_field(0..6).name("name"),
+///     FieldSet::new_field(6..9).name("age").pad_with('0').justify(Justify::Right),
+///     FieldSet::new_field(11..15).name("height"),
+/// ]);
+///
+/// assert_eq!(format!("{:?}", fields), format!("{:?}", by_range));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldSetBuilder {
+    offset: usize,
+    fields: Vec<FieldSet>,
+}
+
+impl FieldSetBuilder {
+    /// Creates a builder whose first field starts at byte 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder whose first field starts at the given byte offset.
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSetBuilder;
+    ///
+    /// let fields = FieldSetBuilder::starting_at(10).field("foo", 2).build();
+    /// # assert_eq!(fields.validate().width(), 12);
+    /// ```
+    pub fn starting_at(offset: usize) -> Self {
+        Self {
+            offset,
+            fields: vec![],
+        }
+    }
+
+    /// Adds a named field of the given width in bytes.
+    pub fn field<T: Into<String>>(self, name: T, width: usize) -> Self {
+        self.field_with(name, width, |f| f)
+    }
+
+    /// Adds a named field of the given width in bytes, letting `f` set further options on it.
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::builder()
+    ///     .field_with("amount", 8, |f| f.pad_with('0').justify("right"))
+    ///     .build();
+    /// ```
+    pub fn field_with<T, F>(mut self, name: T, width: usize, f: F) -> Self
+    where
+        T: Into<String>,
+        F: FnOnce(FieldSet) -> FieldSet,
+    {
+        let field = FieldSet::new_field(self.offset..self.offset + width).name(name);
+        self.fields.push(f(field));
+        self.offset += width;
+        self
+    }
+
+    /// Skips the given number of bytes without defining a field for them.
+    pub fn filler(mut self, width: usize) -> Self {
+        self.offset += width;
+        self
+    }
+
+    /// Adds a nested `FieldSet::Seq` built from the fields added by `f`, starting where the
+    /// previous field ended. Useful for nested structs.
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::builder()
+    ///     .field("id", 4)
+    ///     .group(|g| g.field("x", 3).field("y", 3))
+    ///     .build();
+    ///
+    /// let by_range = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..4).name("id"),
+    ///     FieldSet::Seq(vec![
+    ///         FieldSet::new_field(4..7).name("x"),
+    ///         FieldSet::new_field(7..10).name("y"),
+    ///     ]),
+    /// ]);
+    ///
+    /// assert_eq!(format!("{:?}", fields), format!("{:?}", by_range));
+    /// ```
+    pub fn group<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(FieldSetBuilder) -> FieldSetBuilder,
+    {
+        let group = f(Self::starting_at(self.offset));
+        self.offset = group.offset;
+        self.fields.push(FieldSet::Seq(group.fields));
+        self
+    }
+
+    /// The byte offset at which the next field will start.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Consumes the builder and returns the fields as a `FieldSet::Seq`.
+    pub fn build(self) -> FieldSet {
+        FieldSet::Seq(self.fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Justify;
+
+    #[test]
+    fn sequential_ranges() {
+        let fields = FieldSetBuilder::new()
+            .field("a", 3)
+            .filler(2)
+            .field_with("b", 4, |f| f.justify(Justify::Right))
+            .group(|g| g.field("c", 1).filler(1).field("d", 2))
+            .field("e", 5)
+            .build();
+
+        let ranges: Vec<_> = fields
+            .clone()
+            .flatten()
+            .into_iter()
+            .map(|f| (f.name.unwrap(), f.range))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                ("a".to_string(), 0..3),
+                ("b".to_string(), 5..9),
+                ("c".to_string(), 9..10),
+                ("d".to_string(), 11..13),
+                ("e".to_string(), 13..18),
+            ]
+        );
+        assert_eq!(fields.flatten()[1].justify, Justify::Right);
+    }
+
+    #[test]
+    fn offset_tracks_fillers() {
+        let builder = FieldSetBuilder::starting_at(4).field("a", 3).filler(3);
+        assert_eq!(builder.offset(), 10);
+    }
+}
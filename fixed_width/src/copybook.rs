@@ -0,0 +1,1200 @@
+/*!
+Importing record layouts from COBOL copybooks.
+
+A copybook describes a record as a tree of level numbered items. Group items contain other items,
+while elementary items have a `PIC` clause that, together with their `USAGE`, determines how many
+bytes they take up. `Copybook::parse` computes the byte range of every item and turns each record
+into a nested `FieldSet`, so that mainframe extracts can be read without transcribing the layout by
+hand.
+
+```rust
+use fixed_width::copybook::Copybook;
+use std::collections::HashMap;
+
+let copybook = Copybook::parse("
+       01  CUSTOMER-RECORD.
+           05  CUST-ID          PIC 9(5).
+           05  CUST-NAME        PIC X(10).
+           05  FILLER           PIC X(2).
+           05  CUST-BALANCE     PIC S9(5)V99 COMP-3.
+").unwrap();
+
+let record = &copybook.records()[0];
+assert_eq!(record.width(), 21);
+assert_eq!(record.find("CUST-BALANCE").unwrap().range, 17..21);
+
+let fields = copybook.field_set();
+let h: HashMap<String, String> =
+    fixed_width::from_bytes_with_fields(b"00042JOHN SMITH  \x00\x12\x34\x5D", fields).unwrap();
+
+assert_eq!(h.get("CUST-NAME").unwrap(), "JOHN SMITH");
+assert_eq!(h.get("CUST-BALANCE").unwrap(), "-123.45");
+```
+
+Supported clauses are `PIC`/`PICTURE`, `USAGE` (`DISPLAY`, `COMP`, `COMP-3`, `COMP-4`, `COMP-5`,
+`BINARY` and `PACKED-DECIMAL`), `OCCURS`, `REDEFINES`, `SIGN` and `JUSTIFIED`. `VALUE`, `SYNC`,
+`BLANK WHEN ZERO` and level `66` and `88` entries are accepted and ignored. Floating point items
+(`COMP-1` and `COMP-2`) can not be read, so they are only accepted as `FILLER`.
+
+Signed `DISPLAY` items with a non separate sign are read and written with an overpunched sign,
+on the last digit or, with `SIGN LEADING`, on the first digit. Items with a separate sign are read
+and written with `Sign::Always` or `Sign::Separate`. `COMP-3` items are read and written as packed
+decimal and `COMP`, `COMP-4`, `COMP-5` and `BINARY` items as big endian binary integers, see
+`Codec`. The implied decimal point of a `V` picture becomes the scale of the field, so
+`PIC S9(5)V99` is read as a number with two decimals.
+
+`FILLER` items take up space in the record, but are left out of the `FieldSet`. Items with a
+`REDEFINES` clause are left out as well, since they describe the same bytes as the item they
+redefine. Use `CopybookItem::field_set` on a redefining item to read those bytes with its layout.
+Each occurrence of an item with an `OCCURS` clause is named with its subscript, such as
+`AMOUNT(2)`.
+*/
+use crate::{error::Error, Codec, Endian, FieldSet, Justify, Result, Sign};
+use std::{error::Error as StdError, fmt, fs, ops::Range, path::Path};
+
+/// Errors that occur while parsing a copybook.
+#[derive(Debug)]
+pub enum CopybookError {
+    /// The copybook could not be parsed. Contains the line number and a description.
+    Syntax(usize, String),
+    /// A `PIC` clause could not be parsed.
+    InvalidPicture(String),
+    /// A `REDEFINES` clause names an item that does not precede it at the same level.
+    UnknownRedefines(String),
+    /// An item has a usage that can not be read, such as `COMP-1`. Contains the name of the item.
+    UnsupportedUsage(String),
+}
+
+impl fmt::Display for CopybookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CopybookError::Syntax(line, ref msg) => write!(f, "line {}: {}", line, msg),
+            CopybookError::InvalidPicture(ref pic) => write!(f, "invalid picture `{}`", pic),
+            CopybookError::UnknownRedefines(ref name) => {
+                write!(f, "redefined item `{}` not found", name)
+            }
+            CopybookError::UnsupportedUsage(ref name) => {
+                write!(f, "item `{}` has a floating point usage", name)
+            }
+        }
+    }
+}
+
+impl StdError for CopybookError {
+    fn cause(&self) -> Option<&dyn StdError> {
+        None
+    }
+}
+
+/// How the data of an item is stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Usage {
+    /// One character per position of the picture.
+    Display,
+    /// Packed decimal, two digits per byte with the sign in the last half byte. `COMP-3`.
+    Packed,
+    /// Big endian binary integer. `COMP`, `COMP-4`, `COMP-5` and `BINARY`.
+    Binary,
+    /// Single precision floating point. `COMP-1`.
+    Float,
+    /// Double precision floating point. `COMP-2`.
+    Double,
+}
+
+/// The position of the sign of a signed numeric item, as given by its `SIGN` clause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignPosition {
+    /// The sign is carried by the first digit, or is the first byte if separate.
+    Leading,
+    /// The sign is carried by the last digit, or is the last byte if separate. This is the default.
+    Trailing,
+}
+
+/// The category of data described by a picture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Category {
+    /// Letters and spaces only. `PIC A`.
+    Alphabetic,
+    /// Any character. `PIC X`.
+    Alphanumeric,
+    /// Digits, with an optional sign and implied decimal point. `PIC S9V9`.
+    Numeric,
+    /// Display formatted number, such as `PIC ZZ,ZZ9.99-`.
+    NumericEdited,
+}
+
+/// A parsed `PIC` clause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    /// The picture string as written in the copybook.
+    pub picture: String,
+    /// The category of data described by the picture.
+    pub category: Category,
+    /// Whether the picture includes a sign (`S`).
+    pub signed: bool,
+    /// The number of digit positions (`9`) in the picture.
+    pub digits: usize,
+    /// The number of digit positions after the implied decimal point (`V`), including the
+    /// scaling positions (`P`) before the digits.
+    pub scale: usize,
+    /// The number of characters the picture takes up when stored as `DISPLAY`.
+    pub display_len: usize,
+}
+
+impl Picture {
+    /// Parses a picture string such as `S9(7)V99` or `X(20)`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::copybook::{Category, Picture};
+    ///
+    /// let pic = Picture::parse("S9(7)V99").unwrap();
+    ///
+    /// assert_eq!(pic.category, Category::Numeric);
+    /// assert!(pic.signed);
+    /// assert_eq!(pic.digits, 9);
+    /// assert_eq!(pic.scale, 2);
+    /// ```
+    pub fn parse(picture: &str) -> Result<Self> {
+        let invalid = || Error::from(CopybookError::InvalidPicture(picture.to_string()));
+        let mut symbols = vec![];
+        let mut chars = picture.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '(' {
+                let mut count = String::new();
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                    count.push(c);
+                }
+
+                let count: usize = count.trim().parse().map_err(|_| invalid())?;
+                if count == 0 {
+                    return Err(invalid());
+                }
+                let last = *symbols.last().ok_or_else(invalid)?;
+                symbols.extend(std::iter::repeat_n(last, count.saturating_sub(1)));
+            } else {
+                symbols.push(c.to_ascii_uppercase());
+            }
+        }
+
+        if symbols.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut signed = false;
+        let mut digits = 0;
+        let mut scale = 0;
+        let mut display_len = 0;
+        let mut after_point = false;
+        let (mut alpha, mut alnum, mut edited) = (false, false, false);
+
+        for s in &symbols {
+            match s {
+                'S' => signed = true,
+                'V' => after_point = true,
+                // Scaling positions before the digits, as in `PPP999`, move the implied decimal
+                // point to the left of them. Scaling positions after the digits would need a
+                // negative scale, which is not supported.
+                'P' if digits == 0 => {
+                    after_point = true;
+                    scale += 1;
+                }
+                'P' => return Err(invalid()),
+                '9' => {
+                    digits += 1;
+                    display_len += 1;
+                    if after_point {
+                        scale += 1;
+                    }
+                }
+                'A' => {
+                    alpha = true;
+                    display_len += 1;
+                }
+                'X' => {
+                    alnum = true;
+                    display_len += 1;
+                }
+                'Z' | '*' | '.' | ',' | '+' | '-' | '$' | 'B' | '0' | '/' | 'C' | 'R' | 'D'
+                | 'E' => {
+                    edited = true;
+                    display_len += 1;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        let category = if alnum || (alpha && digits > 0) {
+            Category::Alphanumeric
+        } else if alpha {
+            Category::Alphabetic
+        } else if edited {
+            Category::NumericEdited
+        } else {
+            Category::Numeric
+        };
+
+        Ok(Self {
+            picture: picture.to_string(),
+            category,
+            signed,
+            digits,
+            scale,
+            display_len,
+        })
+    }
+}
+
+/// An item of a copybook, either a group of other items or an elementary item with a picture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopybookItem {
+    /// The level number of the item.
+    pub level: u8,
+    /// The name of the item, or `None` for `FILLER`.
+    pub name: Option<String>,
+    /// The byte range of the item within the record. For items with an `OCCURS` clause this
+    /// covers every occurrence.
+    pub range: Range<usize>,
+    /// The picture of an elementary item.
+    pub picture: Option<Picture>,
+    /// How the data of the item is stored.
+    pub usage: Usage,
+    /// The position of the sign, if given by a `SIGN` clause.
+    pub sign: Option<SignPosition>,
+    /// Whether the sign takes up a byte of its own (`SIGN ... SEPARATE`).
+    pub sign_separate: bool,
+    /// Whether the item is `JUSTIFIED RIGHT`.
+    pub justified_right: bool,
+    /// The number of occurrences given by an `OCCURS` clause. For a variable number of
+    /// occurrences, this is the maximum.
+    pub occurs: Option<usize>,
+    /// The name of the item redefined by this item.
+    pub redefines: Option<String>,
+    /// The items contained by a group item. Their ranges are those of the first occurrence.
+    pub children: Vec<CopybookItem>,
+}
+
+impl CopybookItem {
+    /// The width in bytes of the item, including every occurrence.
+    pub fn width(&self) -> usize {
+        self.range.end - self.range.start
+    }
+
+    /// Returns `true` if the item contains other items.
+    pub fn is_group(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Finds an item by name amongst this item and its descendants.
+    pub fn find(&self, name: &str) -> Option<&CopybookItem> {
+        if self.name.as_deref() == Some(name) {
+            return Some(self);
+        }
+
+        self.children.iter().find_map(|c| c.find(name))
+    }
+
+    /// Builds the `FieldSet` for this item. An elementary item becomes a single field, while a
+    /// group item becomes a `FieldSet::Seq` of its children. `FILLER` items and items that
+    /// redefine another item are left out, except when building the field set of the redefining
+    /// item itself.
+    pub fn field_set(&self) -> FieldSet {
+        self.build(0, &[])
+    }
+
+    fn element_width(&self) -> usize {
+        self.width() / self.occurs.unwrap_or(1).max(1)
+    }
+
+    fn build(&self, shift: usize, subscripts: &[usize]) -> FieldSet {
+        match self.occurs {
+            Some(n) => {
+                let width = self.element_width();
+                FieldSet::Seq(
+                    (0..n)
+                        .map(|i| {
+                            let mut subscripts = subscripts.to_vec();
+                            subscripts.push(i + 1);
+                            self.build_occurrence(shift + i * width, &subscripts)
+                        })
+                        .collect(),
+                )
+            }
+            None => self.build_occurrence(shift, subscripts),
+        }
+    }
+
+    fn build_occurrence(&self, shift: usize, subscripts: &[usize]) -> FieldSet {
+        if self.is_group() {
+            let mut seq = vec![];
+            for child in self.children.iter().filter(|c| c.redefines.is_none()) {
+                match (&child.name, child.is_group()) {
+                    (Some(_), _) => seq.push(child.build(shift, subscripts)),
+                    (None, true) => seq.extend(child.build(shift, subscripts)),
+                    (None, false) => {}
+                }
+            }
+            return FieldSet::Seq(seq);
+        }
+
+        let start = self.range.start + shift;
+        let mut field = FieldSet::new_field(start..start + self.element_width());
+
+        if let Some(ref name) = self.name {
+            field = field.name(subscripted(name, subscripts));
+        }
+
+        if let Some(ref pic) = self.picture {
+            let overpunched = pic.signed && !self.sign_separate;
+
+            field = match self.usage {
+                Usage::Display if pic.category == Category::Numeric && overpunched => {
+                    match self.sign {
+                        Some(SignPosition::Leading) => {
+                            field.codec(Codec::Overpunch).sign(Sign::Leading)
+                        }
+                        _ => field.codec(Codec::Overpunch),
+                    }
+                }
+                Usage::Display if pic.category == Category::Numeric => {
+                    let field = field.justify(Justify::Right).pad_with('0');
+
+                    match (pic.signed, self.sign) {
+                        (false, _) => field,
+                        (true, Some(SignPosition::Leading)) => field.sign(Sign::Always),
+                        (true, _) => field.sign(Sign::Separate),
+                    }
+                }
+                Usage::Packed => field.codec(Codec::Packed { signed: pic.signed }),
+                Usage::Binary => field.codec(Codec::Binary {
+                    endian: Endian::Big,
+                    signed: pic.signed,
+                }),
+                _ => field,
+            };
+
+            if pic.category == Category::Numeric && pic.scale > 0 {
+                field = field.scale(pic.scale);
+            }
+        }
+
+        if self.justified_right {
+            field = field.justify(Justify::Right);
+        }
+
+        field
+    }
+}
+
+fn subscripted(name: &str, subscripts: &[usize]) -> String {
+    if subscripts.is_empty() {
+        name.to_string()
+    } else {
+        let subscripts: Vec<String> = subscripts.iter().map(|s| s.to_string()).collect();
+        format!("{}({})", name, subscripts.join(","))
+    }
+}
+
+/// A parsed COBOL copybook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Copybook {
+    records: Vec<CopybookItem>,
+}
+
+impl Copybook {
+    /// Parses the text of a copybook. Both fixed format copybooks, with sequence numbers in
+    /// columns 1-6 and an indicator in column 7, and free format copybooks are accepted. If the
+    /// copybook does not start with an `01` level item, its items are wrapped in an unnamed
+    /// record.
+    pub fn parse(s: &str) -> Result<Self> {
+        let entries = parse_entries(s)?;
+        let mut roots = build_tree(entries);
+
+        if roots.iter().any(|r| r.level != 1 && r.level != 77) {
+            roots = vec![CopybookItem {
+                level: 1,
+                name: None,
+                range: 0..0,
+                picture: None,
+                usage: Usage::Display,
+                sign: None,
+                sign_separate: false,
+                justified_right: false,
+                occurs: None,
+                redefines: None,
+                children: roots,
+            }];
+        }
+
+        for root in &mut roots {
+            layout(root, 0)?;
+        }
+
+        Ok(Self { records: roots })
+    }
+
+    /// Reads and parses a copybook from a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// The records (`01` and `77` level items) defined by the copybook.
+    pub fn records(&self) -> &[CopybookItem] {
+        &self.records
+    }
+
+    /// Finds a record by name.
+    pub fn record(&self, name: &str) -> Option<&CopybookItem> {
+        self.records
+            .iter()
+            .find(|r| r.name.as_deref() == Some(name))
+    }
+
+    /// Builds the `FieldSet` of the first record of the copybook.
+    pub fn field_set(&self) -> FieldSet {
+        self.records
+            .first()
+            .map_or(FieldSet::Seq(vec![]), CopybookItem::field_set)
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "PIC",
+    "PICTURE",
+    "REDEFINES",
+    "OCCURS",
+    "USAGE",
+    "VALUE",
+    "VALUES",
+    "SIGN",
+    "LEADING",
+    "TRAILING",
+    "JUST",
+    "JUSTIFIED",
+    "SYNC",
+    "SYNCHRONIZED",
+    "BLANK",
+    "GLOBAL",
+    "EXTERNAL",
+    "DISPLAY",
+    "COMP",
+    "COMPUTATIONAL",
+    "COMP-1",
+    "COMPUTATIONAL-1",
+    "COMP-2",
+    "COMPUTATIONAL-2",
+    "COMP-3",
+    "COMPUTATIONAL-3",
+    "COMP-4",
+    "COMPUTATIONAL-4",
+    "COMP-5",
+    "COMPUTATIONAL-5",
+    "BINARY",
+    "PACKED-DECIMAL",
+    "INDEXED",
+    "ASCENDING",
+    "DESCENDING",
+    "DEPENDING",
+];
+
+fn is_keyword(token: &str) -> bool {
+    KEYWORDS.contains(&token.to_ascii_uppercase().as_str())
+}
+
+fn usage_of(token: &str) -> Option<Usage> {
+    match token.to_ascii_uppercase().as_str() {
+        "DISPLAY" => Some(Usage::Display),
+        "COMP-3" | "COMPUTATIONAL-3" | "PACKED-DECIMAL" => Some(Usage::Packed),
+        "COMP" | "COMPUTATIONAL" | "COMP-4" | "COMPUTATIONAL-4" | "COMP-5" | "COMPUTATIONAL-5"
+        | "BINARY" => Some(Usage::Binary),
+        "COMP-1" | "COMPUTATIONAL-1" => Some(Usage::Float),
+        "COMP-2" | "COMPUTATIONAL-2" => Some(Usage::Double),
+        _ => None,
+    }
+}
+
+// Strips comments, sequence numbers and identification areas, returning the text of each line.
+fn source_lines(s: &str) -> Vec<(usize, String)> {
+    let mut lines = vec![];
+
+    for (i, line) in s.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let area: String = chars.iter().take(6).collect();
+        let fixed = chars.len() > 6
+            && (area.chars().all(|c| c.is_ascii_digit()) || area.chars().all(|c| c == ' '))
+            && matches!(chars[6], ' ' | '*' | '/' | '-' | 'D' | 'd');
+
+        let text: String = if fixed {
+            if matches!(chars[6], '*' | '/') {
+                continue;
+            }
+            chars.iter().skip(7).take(65).collect()
+        } else {
+            line.to_string()
+        };
+
+        let text = match text.find("*>") {
+            Some(i) => &text[..i],
+            None => text.as_str(),
+        };
+
+        if text.trim_start().starts_with('*') {
+            continue;
+        }
+
+        lines.push((i + 1, text.to_string()));
+    }
+
+    lines
+}
+
+// Splits the copybook into sentences, each a list of tokens terminated by a period.
+fn sentences(s: &str) -> Vec<(usize, Vec<String>)> {
+    let mut sentences = vec![];
+    let mut tokens: Vec<String> = vec![];
+    let mut first_line = 0;
+
+    for (line, text) in source_lines(s) {
+        let mut chars = text.chars().peekable();
+        let mut token = String::new();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' | '"' => {
+                    token.push(c);
+                    for q in chars.by_ref() {
+                        token.push(q);
+                        if q == c {
+                            break;
+                        }
+                    }
+                }
+                '.' if chars.peek().is_none_or(|n| n.is_whitespace()) => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                    if !tokens.is_empty() {
+                        sentences.push((first_line, std::mem::take(&mut tokens)));
+                    }
+                }
+                ',' | ';' if chars.peek().is_none_or(|n| n.is_whitespace()) => {}
+                c if c.is_whitespace() => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                }
+                c => {
+                    if tokens.is_empty() && token.is_empty() {
+                        first_line = line;
+                    }
+                    token.push(c);
+                }
+            }
+        }
+
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+    }
+
+    if !tokens.is_empty() {
+        sentences.push((first_line, tokens));
+    }
+
+    sentences
+}
+
+// Parses the items of a copybook in order, along with the usage given by their own `USAGE` clause.
+fn parse_entries(s: &str) -> Result<Vec<(CopybookItem, Option<Usage>)>> {
+    let mut entries = vec![];
+
+    for (line, tokens) in sentences(s) {
+        let syntax = |msg: String| Error::from(CopybookError::Syntax(line, msg));
+        let mut tokens = tokens.into_iter().peekable();
+
+        let level: u8 = match tokens.next() {
+            Some(t) => t
+                .parse()
+                .map_err(|_| syntax(format!("expected a level number, found `{}`", t)))?,
+            None => continue,
+        };
+
+        if level == 66 || level == 88 {
+            continue;
+        }
+
+        if level == 0 || (level > 49 && level != 77) {
+            return Err(syntax(format!("invalid level number {}", level)));
+        }
+
+        let name = match tokens.peek() {
+            Some(t) if !is_keyword(t) => {
+                let name = tokens.next().unwrap();
+                if name.eq_ignore_ascii_case("FILLER") {
+                    None
+                } else {
+                    Some(name)
+                }
+            }
+            _ => None,
+        };
+
+        let mut item = CopybookItem {
+            level,
+            name,
+            range: 0..0,
+            picture: None,
+            usage: Usage::Display,
+            sign: None,
+            sign_separate: false,
+            justified_right: false,
+            occurs: None,
+            redefines: None,
+            children: vec![],
+        };
+        let mut usage = None;
+
+        while let Some(token) = tokens.next() {
+            let upper = token.to_ascii_uppercase();
+            let mut next = |what: &str| {
+                tokens
+                    .next()
+                    .ok_or_else(|| syntax(format!("expected {} after {}", what, upper)))
+            };
+
+            match upper.as_str() {
+                "PIC" | "PICTURE" => {
+                    let mut pic = next("a picture")?;
+                    if pic.eq_ignore_ascii_case("IS") {
+                        pic = next("a picture")?;
+                    }
+                    item.picture = Some(Picture::parse(&pic)?);
+                }
+                "REDEFINES" => item.redefines = Some(next("a name")?),
+                "USAGE" => {
+                    let mut u = next("a usage")?;
+                    if u.eq_ignore_ascii_case("IS") {
+                        u = next("a usage")?;
+                    }
+                    usage = Some(
+                        usage_of(&u).ok_or_else(|| syntax(format!("unsupported usage `{}`", u)))?,
+                    );
+                }
+                "OCCURS" => {
+                    let count = next("a count")?;
+                    let mut count: usize = count
+                        .parse()
+                        .map_err(|_| syntax(format!("invalid OCCURS count `{}`", count)))?;
+
+                    while let Some(t) = tokens.peek() {
+                        match t.to_ascii_uppercase().as_str() {
+                            "TO" => {
+                                tokens.next();
+                                let max = tokens.next().unwrap_or_default();
+                                count = max.parse().map_err(|_| {
+                                    syntax(format!("invalid OCCURS count `{}`", max))
+                                })?;
+                            }
+                            "TIMES" => {
+                                tokens.next();
+                            }
+                            "DEPENDING" | "ASCENDING" | "DESCENDING" | "INDEXED" => {
+                                tokens.next();
+                                while tokens.peek().is_some_and(|t| !is_keyword(t)) {
+                                    tokens.next();
+                                }
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    item.occurs = Some(count);
+                }
+                "SIGN" | "LEADING" | "TRAILING" => {
+                    let mut position = upper.clone();
+                    if upper == "SIGN" {
+                        position = next("LEADING or TRAILING")?.to_ascii_uppercase();
+                        if position == "IS" {
+                            position = next("LEADING or TRAILING")?.to_ascii_uppercase();
+                        }
+                    }
+
+                    item.sign = match position.as_str() {
+                        "LEADING" => Some(SignPosition::Leading),
+                        "TRAILING" => Some(SignPosition::Trailing),
+                        _ => return Err(syntax(format!("invalid sign position `{}`", position))),
+                    };
+
+                    if tokens
+                        .peek()
+                        .is_some_and(|t| t.eq_ignore_ascii_case("SEPARATE"))
+                    {
+                        tokens.next();
+                        item.sign_separate = true;
+                        if tokens
+                            .peek()
+                            .is_some_and(|t| t.eq_ignore_ascii_case("CHARACTER"))
+                        {
+                            tokens.next();
+                        }
+                    }
+                }
+                "JUST" | "JUSTIFIED" => {
+                    item.justified_right = true;
+                    if tokens
+                        .peek()
+                        .is_some_and(|t| t.eq_ignore_ascii_case("RIGHT"))
+                    {
+                        tokens.next();
+                    }
+                }
+                "VALUE" | "VALUES" | "BLANK" => {
+                    while tokens.peek().is_some_and(|t| !is_keyword(t)) {
+                        tokens.next();
+                    }
+                }
+                "SYNC" | "SYNCHRONIZED" => {
+                    if tokens.peek().is_some_and(|t| {
+                        t.eq_ignore_ascii_case("LEFT") || t.eq_ignore_ascii_case("RIGHT")
+                    }) {
+                        tokens.next();
+                    }
+                }
+                "GLOBAL" | "EXTERNAL" => {}
+                _ => match usage_of(&upper) {
+                    Some(u) => usage = Some(u),
+                    None => return Err(syntax(format!("unexpected `{}`", token))),
+                },
+            }
+        }
+
+        entries.push((item, usage));
+    }
+
+    Ok(entries)
+}
+
+// Nests the items under their groups. An item without a `USAGE` clause takes the usage of its
+// group, so that an explicit `USAGE DISPLAY` under a `COMP-3` group stays `DISPLAY`.
+fn build_tree(entries: Vec<(CopybookItem, Option<Usage>)>) -> Vec<CopybookItem> {
+    let mut roots: Vec<CopybookItem> = vec![];
+    let mut stack: Vec<CopybookItem> = vec![];
+
+    for (mut item, usage) in entries {
+        while stack
+            .last()
+            .is_some_and(|top| top.level >= item.level || top.level == 77 || item.level == 77)
+        {
+            close(&mut stack, &mut roots);
+        }
+
+        item.usage = usage.unwrap_or(stack.last().map_or(Usage::Display, |group| group.usage));
+        stack.push(item);
+    }
+
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn close(stack: &mut Vec<CopybookItem>, roots: &mut Vec<CopybookItem>) {
+    let item = stack.pop().unwrap();
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(item),
+        None => roots.push(item),
+    }
+}
+
+// Assigns byte ranges to the item and its children, returning the width of the item.
+fn layout(item: &mut CopybookItem, start: usize) -> Result<usize> {
+    let element = if item.is_group() {
+        let mut offset = start;
+        let mut end = start;
+        let mut starts: Vec<(Option<String>, usize)> = vec![];
+
+        for child in &mut item.children {
+            let child_start = match child.redefines {
+                Some(ref name) => starts
+                    .iter()
+                    .find(|(n, _)| n.as_deref() == Some(name.as_str()))
+                    .map(|(_, s)| *s)
+                    .ok_or_else(|| CopybookError::UnknownRedefines(name.clone()))?,
+                None => offset,
+            };
+
+            let width = layout(child, child_start)?;
+            starts.push((child.name.clone(), child_start));
+
+            if child.redefines.is_none() {
+                offset = child_start + width;
+            }
+            end = end.max(child_start + width);
+        }
+
+        end - start
+    } else {
+        match (&item.name, item.usage) {
+            (Some(name), Usage::Float | Usage::Double) => {
+                return Err(CopybookError::UnsupportedUsage(name.clone()).into())
+            }
+            _ => elementary_width(item),
+        }
+    };
+
+    let width = element * item.occurs.unwrap_or(1);
+    item.range = start..start + width;
+    Ok(width)
+}
+
+fn elementary_width(item: &CopybookItem) -> usize {
+    let pic = match item.picture {
+        Some(ref pic) => pic,
+        None => {
+            return match item.usage {
+                Usage::Float => 4,
+                Usage::Double => 8,
+                _ => 0,
+            }
+        }
+    };
+
+    match item.usage {
+        Usage::Display => pic.display_len + usize::from(pic.signed && item.sign_separate),
+        Usage::Packed => pic.digits / 2 + 1,
+        Usage::Binary => match pic.digits {
+            0..=4 => 2,
+            5..=9 => 4,
+            _ => 8,
+        },
+        Usage::Float => 4,
+        Usage::Double => 8,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FieldConfig;
+
+    const COPYBOOK: &str = "
+000100* Account master record
+000200 01  ACCOUNT-RECORD.
+000300     05  ACCT-ID                 PIC 9(8).
+000400     05  ACCT-NAME               PIC X(20).
+000500     05  ACCT-TYPE               PIC X.
+000600         88  ACCT-CHECKING       VALUE 'C'.
+000700         88  ACCT-SAVINGS        VALUE 'S'.
+000800     05  FILLER                  PIC X(3).
+000900     05  ACCT-BALANCE            PIC S9(9)V99 COMP-3.
+001000     05  ACCT-LIMIT              PIC S9(4) USAGE IS COMP.
+001100     05  ACCT-OPENED.
+001200         10  OPENED-YEAR         PIC 9(4).
+001300         10  OPENED-MONTH        PIC 99.
+001400         10  OPENED-DAY          PIC 99.
+001500     05  ACCT-OPENED-NUM REDEFINES ACCT-OPENED
+001600                                 PIC 9(8).
+001700     05  ACCT-HISTORY OCCURS 3 TIMES.
+001800         10  HIST-AMOUNT         PIC S9(5)V99
+001900                                 SIGN IS LEADING SEPARATE.
+002000         10  HIST-CODE           PIC XX.
+002100     05  ACCT-RATE               PIC V9(4) COMP-3.
+002200     05  ACCT-FLAGS              PIC X OCCURS 2.
+";
+
+    fn ranges(fields: FieldSet) -> Vec<(Option<String>, Range<usize>)> {
+        fields
+            .flatten()
+            .into_iter()
+            .map(|FieldConfig { name, range, .. }| (name, range))
+            .collect()
+    }
+
+    #[test]
+    fn pictures() {
+        let pic = Picture::parse("X(10)").unwrap();
+        assert_eq!(pic.category, Category::Alphanumeric);
+        assert_eq!(pic.display_len, 10);
+
+        let pic = Picture::parse("s9(3)v9(2)").unwrap();
+        assert_eq!(pic.category, Category::Numeric);
+        assert!(pic.signed);
+        assert_eq!((pic.digits, pic.scale, pic.display_len), (5, 2, 5));
+
+        let pic = Picture::parse("ZZ,ZZ9.99-").unwrap();
+        assert_eq!(pic.category, Category::NumericEdited);
+        assert_eq!(pic.display_len, 10);
+
+        let pic = Picture::parse("AAA").unwrap();
+        assert_eq!(pic.category, Category::Alphabetic);
+
+        assert!(Picture::parse("9(x)").is_err());
+        assert!(Picture::parse("Q").is_err());
+        assert!(Picture::parse("X(0)").is_err());
+
+        let pic = Picture::parse("SPPP999").unwrap();
+        assert_eq!((pic.digits, pic.scale, pic.display_len), (3, 6, 3));
+        let pic = Picture::parse("VP(2)99").unwrap();
+        assert_eq!((pic.digits, pic.scale, pic.display_len), (2, 4, 2));
+        assert!(Picture::parse("999PPP").is_err());
+    }
+
+    #[test]
+    fn item_ranges() {
+        let copybook = Copybook::parse(COPYBOOK).unwrap();
+        let record = copybook.record("ACCOUNT-RECORD").unwrap();
+
+        let range = |name| record.find(name).unwrap().range.clone();
+
+        assert_eq!(range("ACCT-ID"), 0..8);
+        assert_eq!(range("ACCT-NAME"), 8..28);
+        assert_eq!(range("ACCT-TYPE"), 28..29);
+        assert_eq!(range("ACCT-BALANCE"), 32..38);
+        assert_eq!(range("ACCT-LIMIT"), 38..40);
+        assert_eq!(range("ACCT-OPENED"), 40..48);
+        assert_eq!(range("ACCT-OPENED-NUM"), 40..48);
+        assert_eq!(range("ACCT-HISTORY"), 48..78);
+        assert_eq!(range("HIST-AMOUNT"), 48..56);
+        assert_eq!(range("ACCT-RATE"), 78..81);
+        assert_eq!(range("ACCT-FLAGS"), 81..83);
+        assert_eq!(record.width(), 83);
+
+        let balance = record.find("ACCT-BALANCE").unwrap();
+        assert_eq!(balance.usage, Usage::Packed);
+        assert_eq!(balance.picture.as_ref().unwrap().scale, 2);
+
+        let amount = record.find("HIST-AMOUNT").unwrap();
+        assert_eq!(amount.sign, Some(SignPosition::Leading));
+        assert!(amount.sign_separate);
+    }
+
+    #[test]
+    fn record_field_set() {
+        let copybook = Copybook::parse(COPYBOOK).unwrap();
+        let n = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            ranges(copybook.field_set()),
+            vec![
+                (n("ACCT-ID"), 0..8),
+                (n("ACCT-NAME"), 8..28),
+                (n("ACCT-TYPE"), 28..29),
+                (n("ACCT-BALANCE"), 32..38),
+                (n("ACCT-LIMIT"), 38..40),
+                (n("OPENED-YEAR"), 40..44),
+                (n("OPENED-MONTH"), 44..46),
+                (n("OPENED-DAY"), 46..48),
+                (n("HIST-AMOUNT(1)"), 48..56),
+                (n("HIST-CODE(1)"), 56..58),
+                (n("HIST-AMOUNT(2)"), 58..66),
+                (n("HIST-CODE(2)"), 66..68),
+                (n("HIST-AMOUNT(3)"), 68..76),
+                (n("HIST-CODE(3)"), 76..78),
+                (n("ACCT-RATE"), 78..81),
+                (n("ACCT-FLAGS(1)"), 81..82),
+                (n("ACCT-FLAGS(2)"), 82..83),
+            ]
+        );
+
+        let report = copybook.field_set().validate();
+        assert!(report.is_valid());
+        assert_eq!(report.width(), 83);
+    }
+
+    #[test]
+    fn nested_field_sets() {
+        let copybook = Copybook::parse(COPYBOOK).unwrap();
+
+        let record = copybook.field_set();
+        let children: Vec<FieldSet> = record.into_iter().collect();
+
+        assert!(matches!(children[5], FieldSet::Seq(ref s) if s.len() == 3));
+        match children[6] {
+            FieldSet::Seq(ref occurrences) => {
+                assert_eq!(occurrences.len(), 3);
+                assert!(matches!(occurrences[0], FieldSet::Seq(ref s) if s.len() == 2));
+            }
+            _ => panic!("expected OCCURS to be a Seq"),
+        }
+
+        let redefined = copybook.records()[0].find("ACCT-OPENED-NUM").unwrap();
+        assert_eq!(
+            ranges(redefined.field_set()),
+            vec![(n("ACCT-OPENED-NUM"), 40..48)]
+        );
+
+        fn n(s: &str) -> Option<String> {
+            Some(s.to_string())
+        }
+    }
+
+    #[test]
+    fn deserialize_display_fields() {
+        #[derive(serde_derive::Deserialize)]
+        struct Opened {
+            year: u16,
+            month: u8,
+            day: u8,
+        }
+
+        #[derive(serde_derive::Deserialize)]
+        struct Record {
+            id: u32,
+            name: String,
+            opened: Opened,
+            balance: i32,
+        }
+
+        let copybook = Copybook::parse(
+            "
+              05 ID       PIC 9(4).
+              05 NAME     PIC X(6).
+              05 OPENED.
+                 10 YEAR  PIC 9(4).
+                 10 MONTH PIC 99.
+                 10 DAY   PIC 99.
+              05 BALANCE  PIC S9(4).
+            ",
+        )
+        .unwrap();
+
+        let record: Record =
+            crate::from_str_with_fields("0042ALICE 20240131002J", copybook.field_set()).unwrap();
+
+        assert_eq!(record.id, 42);
+        assert_eq!(record.name, "ALICE");
+        assert_eq!(
+            (record.opened.year, record.opened.month, record.opened.day),
+            (2024, 1, 31)
+        );
+        assert_eq!(record.balance, -21);
+    }
+
+    #[test]
+    fn free_format_and_multiple_records() {
+        let copybook = Copybook::parse(
+            "01 HEADER-REC. *> file header
+               05 REC-TYPE PIC X VALUE 'H'.
+               05 RUN-DATE PIC 9(8).
+             01 DETAIL-REC.
+               05 REC-TYPE PIC X.
+               05 AMOUNT PIC S9(7)V99 COMP-3.
+             77 STANDALONE PIC X(4).",
+        )
+        .unwrap();
+
+        let names: Vec<_> = copybook
+            .records()
+            .iter()
+            .map(|r| (r.name.clone().unwrap(), r.width()))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("HEADER-REC".to_string(), 9),
+                ("DETAIL-REC".to_string(), 6),
+                ("STANDALONE".to_string(), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_and_float_widths() {
+        let copybook = Copybook::parse(
+            "01 R.
+               05 A PIC S9(4) COMP.
+               05 B PIC 9(9) BINARY.
+               05 C PIC S9(18) COMP-5.
+               05 FILLER COMP-1.
+               05 FILLER COMP-2.
+               05 F PIC S9(3) SIGN TRAILING SEPARATE.",
+        )
+        .unwrap();
+
+        let widths: Vec<usize> = copybook.records()[0]
+            .children
+            .iter()
+            .map(CopybookItem::width)
+            .collect();
+
+        assert_eq!(widths, vec![2, 4, 8, 4, 8, 4]);
+    }
+
+    #[test]
+    fn explicit_display_in_packed_group() {
+        let copybook = Copybook::parse(
+            "01 R.
+               05 AMOUNTS COMP-3.
+                  10 A PIC S9(3).
+                  10 B PIC 9(3) USAGE DISPLAY.",
+        )
+        .unwrap();
+
+        let record = &copybook.records()[0];
+        assert_eq!(record.find("A").unwrap().usage, Usage::Packed);
+        assert_eq!(record.find("B").unwrap().usage, Usage::Display);
+        assert_eq!(record.find("B").unwrap().range, 2..5);
+    }
+
+    #[test]
+    fn separate_signs() {
+        let copybook = Copybook::parse(
+            "01 R.
+               05 A PIC S9(3) SIGN LEADING SEPARATE.
+               05 B PIC S9(3)V9 SIGN TRAILING SEPARATE.",
+        )
+        .unwrap();
+
+        let (a, b): (i32, f64) =
+            crate::from_str_with_fields("-0420125+", copybook.field_set()).unwrap();
+
+        assert_eq!((a, b), (-42, 12.5));
+    }
+
+    #[test]
+    fn leading_overpunched_signs() {
+        let copybook = Copybook::parse(
+            "01 R.
+               05 A PIC S9(3) SIGN LEADING.
+               05 B PIC S9(3)V9 SIGN IS TRAILING.",
+        )
+        .unwrap();
+
+        let fields = copybook.field_set();
+        let (a, b): (i32, f64) = crate::from_str_with_fields("J23012E", fields.clone()).unwrap();
+        assert_eq!((a, b), (-123, 12.5));
+
+        let mut wrtr = crate::Writer::from_memory();
+        crate::to_writer_with_fields(&mut wrtr, &(-123, 12.5), fields).unwrap();
+        assert_eq!(Into::<Vec<u8>>::into(wrtr), b"J23012E".to_vec());
+    }
+
+    #[test]
+    fn errors() {
+        match Copybook::parse("01 R.\n  05 A PIC X(2) BOGUS.") {
+            Err(Error::CopybookError(CopybookError::Syntax(2, _))) => {}
+            r => panic!("expected a syntax error, got {:?}", r),
+        }
+
+        match Copybook::parse("01 R.\n  05 A PIC X.\n  05 B REDEFINES C PIC X.") {
+            Err(Error::CopybookError(CopybookError::UnknownRedefines(name))) => {
+                assert_eq!(name, "C")
+            }
+            r => panic!("expected an unknown redefines error, got {:?}", r),
+        }
+
+        match Copybook::parse("01 R.\n  05 A PIC X.\n  05 B COMP-2.") {
+            Err(Error::CopybookError(CopybookError::UnsupportedUsage(name))) => {
+                assert_eq!(name, "B")
+            }
+            r => panic!("expected an unsupported usage error, got {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{de::DeserializeError, ser::SerializeError};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+// A part of a date format. Every part has a fixed width, so that the position of each part in a
+// field is known up front.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Year,
+    ShortYear,
+    Century,
+    Month,
+    Day,
+    DayOfYear,
+    Hour,
+    Minute,
+    Second,
+    Literal(char),
+}
+
+impl Item {
+    fn width(self) -> usize {
+        match self {
+            Item::Year => 4,
+            Item::Century => 1,
+            Item::DayOfYear => 3,
+            Item::Literal(c) => c.len_utf8(),
+            _ => 2,
+        }
+    }
+}
+
+fn items(format: &str) -> Result<Vec<Item>, String> {
+    let mut items = vec![];
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            items.push(Item::Literal(c));
+            continue;
+        }
+
+        items.push(match chars.next() {
+            Some('Y') => Item::Year,
+            Some('y') => Item::ShortYear,
+            Some('C') => Item::Century,
+            Some('m') => Item::Month,
+            Some('d') => Item::Day,
+            Some('j') => Item::DayOfYear,
+            Some('H') => Item::Hour,
+            Some('M') => Item::Minute,
+            Some('S') => Item::Second,
+            Some('%') => Item::Literal('%'),
+            Some(c) => return Err(format!("unsupported date format specifier `%{}`", c)),
+            None => return Err(format!("date format `{}` ends with `%`", format)),
+        });
+    }
+
+    Ok(items)
+}
+
+#[derive(Default)]
+struct Parts {
+    year: Option<i32>,
+    short_year: Option<i32>,
+    century: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    day_of_year: Option<u32>,
+    hour: Option<u32>,
+    minute: Option<u32>,
+    second: Option<u32>,
+}
+
+/// Reads the text of a date field into ISO 8601 text: `2024-01-31`, `12:34:56` or
+/// `2024-01-31T12:34:56`, depending on the format. Blank fields are read as empty text, and so are
+/// all zero fields if the format has a date, since `000000` is a valid time but not a valid date.
+pub(crate) fn parse(format: &str, text: &str) -> Result<String, DeserializeError> {
+    let invalid = |msg: String| DeserializeError::InvalidDate(msg);
+    let items = items(format).map_err(invalid)?;
+    let width: usize = items.iter().map(|i| i.width()).sum();
+    let text = text.trim();
+
+    let has_date = items.iter().any(|i| {
+        matches!(
+            i,
+            Item::Year
+                | Item::ShortYear
+                | Item::Century
+                | Item::Month
+                | Item::Day
+                | Item::DayOfYear
+        )
+    });
+    if text.is_empty() || (has_date && text.bytes().all(|b| b == b'0')) {
+        return Ok(String::new());
+    }
+
+    // Leading zeros are lost when a date is stored as a number, such as in a packed field.
+    let text = match text.bytes().all(|b| b.is_ascii_digit()) && text.len() < width {
+        true => format!("{:0>width$}", text, width = width),
+        false => text.to_string(),
+    };
+
+    let mismatch = || {
+        invalid(format!(
+            "`{}` does not match the date format `{}`",
+            text, format
+        ))
+    };
+    if text.len() != width {
+        return Err(mismatch());
+    }
+
+    let mut parts = Parts::default();
+    let mut pos = 0;
+
+    for item in items {
+        let part = text.get(pos..pos + item.width()).ok_or_else(mismatch)?;
+        pos += item.width();
+
+        if let Item::Literal(c) = item {
+            if !part.starts_with(c) {
+                return Err(mismatch());
+            }
+            continue;
+        }
+
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(mismatch());
+        }
+
+        let n: u32 = part.parse().map_err(|_| mismatch())?;
+        match item {
+            Item::Year => parts.year = Some(n as i32),
+            Item::ShortYear => parts.short_year = Some(n as i32),
+            Item::Century => parts.century = Some(n as i32),
+            Item::Month => parts.month = Some(n),
+            Item::Day => parts.day = Some(n),
+            Item::DayOfYear => parts.day_of_year = Some(n),
+            Item::Hour => parts.hour = Some(n),
+            Item::Minute => parts.minute = Some(n),
+            Item::Second => parts.second = Some(n),
+            Item::Literal(_) => {}
+        }
+    }
+
+    let year = match (parts.year, parts.century, parts.short_year) {
+        (Some(y), _, _) => Some(y),
+        (None, Some(c), Some(y)) => Some(1900 + 100 * c + y),
+        (None, None, Some(y)) if y < 70 => Some(2000 + y),
+        (None, None, Some(y)) => Some(1900 + y),
+        _ => None,
+    };
+
+    let out_of_range = || invalid(format!("`{}` is not a valid date or time", text));
+
+    let date = match (year, parts.month, parts.day, parts.day_of_year) {
+        (None, None, None, None) => None,
+        (Some(y), None, None, Some(o)) => {
+            Some(NaiveDate::from_yo_opt(y, o).ok_or_else(out_of_range)?)
+        }
+        (Some(y), Some(m), Some(d), None) => {
+            Some(NaiveDate::from_ymd_opt(y, m, d).ok_or_else(out_of_range)?)
+        }
+        _ => return Err(invalid(format!("date format `{}` is incomplete", format))),
+    };
+
+    let time = match (parts.hour, parts.minute, parts.second) {
+        (None, None, None) => None,
+        (h, m, s) => Some(
+            NaiveTime::from_hms_opt(h.unwrap_or(0), m.unwrap_or(0), s.unwrap_or(0))
+                .ok_or_else(out_of_range)?,
+        ),
+    };
+
+    match (date, time) {
+        (Some(d), Some(t)) => Ok(format!("{}T{}", iso_date(d), iso_time(t))),
+        (Some(d), None) => Ok(iso_date(d)),
+        (None, Some(t)) => Ok(iso_time(t)),
+        (None, None) => Err(invalid(format!("date format `{}` is empty", format))),
+    }
+}
+
+/// Writes ISO 8601 date, time or date and time text in the given date format. Empty text is
+/// written as an empty field.
+pub(crate) fn format(format: &str, text: &str) -> Result<String, SerializeError> {
+    let invalid = |msg: String| SerializeError::InvalidDate(msg);
+    let items = items(format).map_err(invalid)?;
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Ok(String::new());
+    }
+
+    let (date, time) = if let Ok(dt) = text.parse::<NaiveDateTime>() {
+        (Some(dt.date()), Some(dt.time()))
+    } else if let Ok(d) = text.parse::<NaiveDate>() {
+        (Some(d), None)
+    } else if let Ok(t) = text.parse::<NaiveTime>() {
+        (None, Some(t))
+    } else {
+        return Err(invalid(format!("`{}` is not a date or time", text)));
+    };
+
+    if time.is_some_and(|t| t.nanosecond() != 0) {
+        return Err(invalid(format!(
+            "`{}` can not be written without its fraction of a second",
+            text
+        )));
+    }
+
+    let no_date = || {
+        invalid(format!(
+            "`{}` has no date for the format `{}`",
+            text, format
+        ))
+    };
+    let no_time = || {
+        invalid(format!(
+            "`{}` has no time for the format `{}`",
+            text, format
+        ))
+    };
+    let unrepresentable = || {
+        invalid(format!(
+            "the year of `{}` can not be written as `{}`",
+            text, format
+        ))
+    };
+    let has_century = items.contains(&Item::Century);
+    let mut out = String::with_capacity(format.len());
+
+    for item in items {
+        let year = || date.map(|d| d.year()).ok_or_else(no_date);
+
+        match item {
+            Item::Year => match year()? {
+                y @ 0..=9999 => out.push_str(&format!("{:04}", y)),
+                _ => return Err(unrepresentable()),
+            },
+            Item::ShortYear => match year()? {
+                y @ 1900..=2899 if has_century => out.push_str(&format!("{:02}", y % 100)),
+                y @ 1970..=2069 => out.push_str(&format!("{:02}", y % 100)),
+                _ => return Err(unrepresentable()),
+            },
+            Item::Century => match year()? {
+                y @ 1900..=2899 => out.push_str(&(y / 100 - 19).to_string()),
+                _ => return Err(unrepresentable()),
+            },
+            Item::Month => out.push_str(&format!("{:02}", date.ok_or_else(no_date)?.month())),
+            Item::Day => out.push_str(&format!("{:02}", date.ok_or_else(no_date)?.day())),
+            Item::DayOfYear => out.push_str(&format!("{:03}", date.ok_or_else(no_date)?.ordinal())),
+            Item::Hour => out.push_str(&format!("{:02}", time.ok_or_else(no_time)?.hour())),
+            Item::Minute => out.push_str(&format!("{:02}", time.ok_or_else(no_time)?.minute())),
+            Item::Second => out.push_str(&format!("{:02}", time.ok_or_else(no_time)?.second())),
+            Item::Literal(c) => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+fn iso_date(d: NaiveDate) -> String {
+    format!("{:04}-{:02}-{:02}", d.year(), d.month(), d.day())
+}
+
+fn iso_time(t: NaiveTime) -> String {
+    format!("{:02}:{:02}:{:02}", t.hour(), t.minute(), t.second())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_formats() {
+        assert_eq!(parse("%Y%m%d", "20240131").unwrap(), "2024-01-31");
+        assert_eq!(parse("%m%d%y", "013124").unwrap(), "2024-01-31");
+        assert_eq!(parse("%m%d%y", "123199").unwrap(), "1999-12-31");
+        assert_eq!(parse("%y%j", "24060").unwrap(), "2024-02-29");
+        assert_eq!(parse("%C%y%m%d", "0991231").unwrap(), "1999-12-31");
+        assert_eq!(parse("%C%y%m%d", "1240131").unwrap(), "2024-01-31");
+        assert_eq!(parse("%H%M%S", "123456").unwrap(), "12:34:56");
+        assert_eq!(
+            parse("%Y-%m-%d %H:%M", "2024-01-31 12:34").unwrap(),
+            "2024-01-31T12:34:00"
+        );
+        assert_eq!(parse("%m%d%Y", "1312024").unwrap(), "2024-01-31");
+
+        assert_eq!(parse("%Y%m%d", "").unwrap(), "");
+        assert_eq!(parse("%Y%m%d", "00000000").unwrap(), "");
+        assert_eq!(parse("%H%M%S", "").unwrap(), "");
+        assert_eq!(parse("%H%M%S", "000000").unwrap(), "00:00:00");
+
+        assert!(parse("%Y%m%d", "20240230").is_err());
+        assert!(parse("%Y%m%d", "2024013").is_err());
+        assert!(parse("%Y-%m-%d", "2024/01/31").is_err());
+        assert!(parse("%Y%m", "202401").is_err());
+        assert!(parse("%Y%q", "2024").is_err());
+    }
+
+    #[test]
+    fn format_dates() {
+        assert_eq!(format("%Y%m%d", "2024-01-31").unwrap(), "20240131");
+        assert_eq!(format("%m%d%y", "1999-12-31").unwrap(), "123199");
+        assert_eq!(format("%y%j", "2024-02-29").unwrap(), "24060");
+        assert_eq!(format("%C%y%m%d", "2024-01-31").unwrap(), "1240131");
+        assert_eq!(format("%H%M%S", "12:34:56").unwrap(), "123456");
+        assert_eq!(
+            format("%Y%m%d%H%M", "2024-01-31T12:34:00").unwrap(),
+            "202401311234"
+        );
+        assert_eq!(format("%Y%m%d", "").unwrap(), "");
+
+        assert!(format("%y%m%d", "1950-01-01").is_err());
+        assert!(format("%H%M%S", "2024-01-31").is_err());
+        assert!(format("%H%M%S", "12:34:56.5").is_err());
+        assert!(format("%Y%m%d", "not a date").is_err());
+    }
+
+    #[test]
+    fn chrono_round_trip() {
+        use crate::{FieldSet, Writer};
+        use serde_derive::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Account {
+            opened: NaiveDate,
+            closed: Option<NaiveDate>,
+            updated: NaiveDateTime,
+            cutoff: NaiveTime,
+        }
+
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4)
+                .codec(crate::Codec::Packed { signed: false })
+                .format("%C%y%m%d"),
+            FieldSet::new_field(4..10).format("%m%d%y").pad_with('0'),
+            FieldSet::new_field(10..22).format("%Y%m%d%H%M"),
+            FieldSet::new_field(22..28).format("%H%M%S"),
+        ]);
+
+        let bytes = [
+            0x09, 0x91, 0x23, 0x1F, b'0', b'0', b'0', b'0', b'0', b'0', b'2', b'0', b'2', b'4',
+            b'0', b'1', b'3', b'1', b'1', b'2', b'3', b'4', b'2', b'3', b'5', b'9', b'5', b'9',
+        ];
+        let account: Account = crate::from_bytes_with_fields(&bytes, fields.clone()).unwrap();
+
+        assert_eq!(
+            account.opened,
+            NaiveDate::from_ymd_opt(1999, 12, 31).unwrap()
+        );
+        assert_eq!(account.closed, None);
+        assert_eq!(account.updated.to_string(), "2024-01-31 12:34:00");
+        assert_eq!(account.cutoff, NaiveTime::from_hms_opt(23, 59, 59).unwrap());
+
+        let mut wrtr = Writer::from_memory();
+        crate::to_writer_with_fields(&mut wrtr, &account, fields).unwrap();
+        assert_eq!(Into::<Vec<u8>>::into(wrtr), bytes.to_vec());
+
+        let midnight: NaiveTime =
+            crate::from_str_with_fields("000000", FieldSet::new_field(0..6).format("%H%M%S"))
+                .unwrap();
+        assert_eq!(midnight, NaiveTime::MIN);
+    }
+}
@@ -1,9 +1,17 @@
-use crate::{error, FieldSet, FixedWidth};
+#[cfg(feature = "chrono")]
+use crate::date;
+use crate::{
+    error, numeric, Booleans, Codec, Encoding, FieldConfig, FieldSet, FixedWidth, Justify, Sign,
+    Trim,
+};
 use serde::{
     self,
-    de::{self, Deserialize, Error, IntoDeserializer, Visitor},
+    de::{self, value::StrDeserializer, Deserialize, IntoDeserializer, Visitor},
+};
+use std::{
+    borrow::Cow, convert, error::Error as StdError, fmt, iter, num, ops::Range, result::Result,
+    slice, str, vec,
 };
-use std::{convert, error::Error as StdError, fmt, iter, num, result::Result, str, vec};
 
 /// Deserializes a `&str` into the given type that implements `FixedWidth` and `Deserialize`.
 ///
@@ -79,6 +87,72 @@ where
     from_bytes_with_fields(b, T::fields())
 }
 
+/// Deserializes a `&[u8]` into the given type that implements `FixedWidth` and `Deserialize`, in
+/// lenient mode so that every invalid field is found. Returns the record, where invalid fields
+/// hold a default value, along with the errors of the invalid fields. See `Deserializer::lenient`.
+///
+/// ### Example
+///
+/// ```rust
+/// use serde_derive::Deserialize;
+/// use fixed_width::{DeserializeError, FieldSet, FixedWidth};
+///
+/// #[derive(Deserialize)]
+/// struct Record {
+///     pub name: String,
+///     pub room: usize,
+///     pub floor: u8,
+/// }
+///
+/// impl FixedWidth for Record {
+///     fn fields() -> FieldSet {
+///         FieldSet::Seq(vec![
+///             FieldSet::new_field(0..4).name("name"),
+///             FieldSet::new_field(4..8).name("room"),
+///             FieldSet::new_field(8..10).name("floor"),
+///         ])
+///     }
+/// }
+///
+/// let (record, errors) = fixed_width::from_bytes_lenient::<Record>(b"Carl12x4x1").unwrap();
+///
+/// assert_eq!(record.name, "Carl");
+/// assert_eq!(record.room, 0);
+/// assert_eq!(errors.len(), 2);
+///
+/// match &errors[0] {
+///     DeserializeError::Field { name, range, .. } => {
+///         assert_eq!((name.as_deref(), range.clone()), (Some("room"), 4..8))
+///     }
+///     e => panic!("unexpected error {}", e),
+/// }
+/// ```
+pub fn from_bytes_lenient<'de, T>(
+    bytes: &'de [u8],
+) -> Result<(T, Vec<DeserializeError>), error::Error>
+where
+    T: FixedWidth + Deserialize<'de>,
+{
+    let mut de = Deserializer::new(bytes, T::fields()).lenient(true);
+    let record = T::deserialize(&mut de)?;
+    Ok((record, de.into_errors()))
+}
+
+/// Reads a field of a record as an integer, through its codec and sign policy but leaving out its
+/// scale, so that a scaled field is read as its digits. Returns `None` if the field is blank or
+/// null.
+pub(crate) fn read_integer(
+    record: &[u8],
+    field: &FieldConfig,
+    encoding: Encoding,
+) -> Result<Option<i128>, DeserializeError> {
+    let mut field = field.clone();
+    field.scale = 0;
+
+    let mut de = Deserializer::new(record, FieldSet::Item(field)).encoding(encoding);
+    Option::<i128>::deserialize(&mut de)
+}
+
 /// Deserializes `&str` data to the given writer using the provided `Field`s.
 ///
 /// ### Example
@@ -147,8 +221,63 @@ pub enum DeserializeError {
     ParseIntError(num::ParseIntError),
     /// A float value could not be parsed for this field.
     ParseFloatError(num::ParseFloatError),
+    /// A packed decimal or binary field did not hold a valid number.
+    InvalidNumber(String),
+    /// A date field did not hold a valid date or time for its format.
+    InvalidDate(String),
+    /// A boolean field held a value that is not in its mapping.
+    InvalidBool(String, Booleans),
+    /// The code of a record is not one of its record types.
+    UnknownRecordType(String),
+    /// The data ended with a record shorter than the record width. Contains the number of bytes
+    /// of the record.
+    PartialRecord(usize),
+    /// A line is longer than the record width, when reading lines with `Reader::ragged`. Contains
+    /// the number of bytes of the line.
+    LongRecord(usize),
     /// Will never implemente
     WontImplement,
+    /// An error occurred while reading a field.
+    Field {
+        /// Name of the field, if it has one.
+        name: Option<String>,
+        /// Byte range of the field.
+        range: Range<usize>,
+        /// The bytes of the field found in the record, which may be fewer than its range if the
+        /// record is too short.
+        bytes: Vec<u8>,
+        /// The error that occurred.
+        error: Box<DeserializeError>,
+    },
+}
+
+impl DeserializeError {
+    /// The error without the context of the field it occurred in.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{DeserializeError, Error, FieldSet};
+    ///
+    /// let fields = FieldSet::new_field(0..3).name("count");
+    ///
+    /// match fixed_width::from_str_with_fields::<u8>("x12", fields) {
+    ///     Err(Error::DeserializeError(e)) => {
+    ///         assert!(matches!(e.without_context(), DeserializeError::ParseIntError(_)));
+    ///         assert_eq!(
+    ///             e.to_string(),
+    ///             "field `count` (0..3) at column 1, `x12`: invalid digit found in string"
+    ///         );
+    ///     }
+    ///     _ => panic!("expected a parse error"),
+    /// }
+    /// ```
+    pub fn without_context(&self) -> &DeserializeError {
+        match self {
+            DeserializeError::Field { error, .. } => error.without_context(),
+            e => e,
+        }
+    }
 }
 
 impl serde::de::Error for DeserializeError {
@@ -167,7 +296,14 @@ impl StdError for DeserializeError {
             DeserializeError::ParseBoolError(e) => Some(e),
             DeserializeError::ParseIntError(e) => Some(e),
             DeserializeError::ParseFloatError(e) => Some(e),
+            DeserializeError::InvalidNumber(_e) => None,
+            DeserializeError::InvalidDate(_e) => None,
+            DeserializeError::InvalidBool(..) => None,
+            DeserializeError::UnknownRecordType(_e) => None,
+            DeserializeError::PartialRecord(_) => None,
+            DeserializeError::LongRecord(_) => None,
             DeserializeError::WontImplement => None,
+            DeserializeError::Field { error, .. } => Some(error.as_ref()),
         }
     }
 }
@@ -184,7 +320,54 @@ impl fmt::Display for DeserializeError {
             DeserializeError::ParseBoolError(ref e) => write!(f, "{}", e),
             DeserializeError::ParseIntError(ref e) => write!(f, "{}", e),
             DeserializeError::ParseFloatError(ref e) => write!(f, "{}", e),
+            DeserializeError::InvalidNumber(ref e) => write!(f, "{}", e),
+            DeserializeError::InvalidDate(ref e) => write!(f, "{}", e),
+            DeserializeError::InvalidBool(ref s, ref booleans) => write!(
+                f,
+                "`{}` is not a boolean, expected one of {:?} for true or {:?} for false",
+                s, booleans.true_values, booleans.false_values
+            ),
+            DeserializeError::UnknownRecordType(ref code) => {
+                write!(f, "`{}` is not a known record type", code)
+            }
+            DeserializeError::PartialRecord(len) => {
+                write!(f, "data ended with a partial record of {} bytes", len)
+            }
+            DeserializeError::LongRecord(len) => {
+                write!(f, "line of {} bytes is longer than the record width", len)
+            }
             DeserializeError::WontImplement => write!(f, "This will never be implemented."),
+            DeserializeError::Field {
+                ref name,
+                ref range,
+                ref bytes,
+                ref error,
+            } => {
+                match name {
+                    Some(name) => write!(f, "field `{}` ({}..{})", name, range.start, range.end)?,
+                    None => write!(f, "field {}..{}", range.start, range.end)?,
+                }
+
+                write!(
+                    f,
+                    " at column {}, {}: {}",
+                    range.start + 1,
+                    show_bytes(bytes),
+                    error
+                )
+            }
+        }
+    }
+}
+
+// Shows the bytes of a field as text if they are printable UTF-8, or in hexadecimal otherwise.
+fn show_bytes(bytes: &[u8]) -> String {
+    match str::from_utf8(bytes) {
+        Ok("") => "no bytes".to_string(),
+        Ok(s) if !s.chars().any(char::is_control) => format!("`{}`", s),
+        _ => {
+            let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("[{}]", hex.join(" "))
         }
     }
 }
@@ -216,8 +399,13 @@ impl From<num::ParseFloatError> for DeserializeError {
 /// A deserialized for fixed width data. Reads from the given bytes using the provided field
 /// definitions to determine how many bytes to read for each deserialized value.
 pub struct Deserializer<'r> {
-    fields: iter::Peekable<vec::IntoIter<FieldSet>>,
+    fields: iter::Peekable<Fields<'r>>,
     input: &'r [u8],
+    encoding: Encoding,
+    // The name and range of the field being read, to give context to its errors.
+    field: Option<(Option<Cow<'r, str>>, Range<usize>)>,
+    lenient: bool,
+    errors: Vec<DeserializeError>,
 }
 
 impl<'r> Deserializer<'r> {
@@ -247,12 +435,109 @@ impl<'r> Deserializer<'r> {
     /// assert_eq!(h.get("8..10").unwrap(), "99");
     /// ```
     pub fn new(input: &'r [u8], fields: FieldSet) -> Self {
+        Self::from_fields(input, Cow::Owned(fields))
+    }
+
+    /// Creates a new Deserializer from the given bytes and borrowed field definitions, so that
+    /// many records can be read with the same fields without copying them for each record.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Deserializer, FieldSet};
+    /// use serde::Deserialize;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..3),
+    ///     FieldSet::new_field(3..5),
+    /// ]);
+    ///
+    /// for (input, expected) in [(b"Ann32", ("Ann", 32)), (b"Bob41", ("Bob", 41))] {
+    ///     let mut de = Deserializer::with_fields(input, &fields);
+    ///     let (name, age) = <(String, u8)>::deserialize(&mut de).unwrap();
+    ///     assert_eq!((name.as_str(), age), expected);
+    /// }
+    /// ```
+    pub fn with_fields(input: &'r [u8], fields: &'r FieldSet) -> Self {
+        Self::from_fields(input, Cow::Borrowed(fields))
+    }
+
+    fn from_fields(input: &'r [u8], fields: Cow<'r, FieldSet>) -> Self {
         Self {
-            fields: fields.into_iter().peekable(),
+            fields: Fields::new(fields).peekable(),
             input,
+            encoding: Encoding::default(),
+            field: None,
+            lenient: false,
+            errors: vec![],
         }
     }
 
+    /// Sets the encoding of the text in the input. Defaults to `Encoding::Utf8`. Field ranges
+    /// are measured in bytes of the input, before decoding.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Deserializer, Encoding, FieldSet};
+    /// use serde::Deserialize;
+    ///
+    /// // "CAFÉ 42" in EBCDIC code page 037.
+    /// let input = [0xC3, 0xC1, 0xC6, 0x71, 0x40, 0xF4, 0xF2];
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..5),
+    ///     FieldSet::new_field(5..7),
+    /// ]);
+    ///
+    /// let mut de = Deserializer::new(&input, fields).encoding(Encoding::Cp037);
+    /// let (name, n) = <(String, u8)>::deserialize(&mut de).unwrap();
+    ///
+    /// assert_eq!(name, "CAFÉ");
+    /// assert_eq!(n, 42);
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Keeps reading when a field cannot be deserialized, so that every invalid field of the input
+    /// is found. The value of an invalid field is `0`, `false`, an empty string or `None`
+    /// depending on its type, and its error is kept in `errors`. Errors that are not tied to a
+    /// single field, such as running out of fields, still stop deserialization. Defaults to
+    /// `false`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Deserializer, FieldSet};
+    /// use serde::Deserialize;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..4).name("name"),
+    ///     FieldSet::new_field(4..6).name("age"),
+    ///     FieldSet::new_field(6..9).name("height"),
+    /// ]);
+    ///
+    /// let mut de = Deserializer::new(b"Ann x2abc", fields).lenient(true);
+    /// let (name, age, height) = <(String, u8, u16)>::deserialize(&mut de).unwrap();
+    ///
+    /// assert_eq!((name.as_str(), age, height), ("Ann", 0, 0));
+    /// assert_eq!(de.errors().len(), 2);
+    /// ```
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// The errors of the invalid fields read so far in lenient mode, in the order they were read.
+    pub fn errors(&self) -> &[DeserializeError] {
+        &self.errors
+    }
+
+    pub(crate) fn into_errors(self) -> Vec<DeserializeError> {
+        self.errors
+    }
+
     /// Gets a reference to the underlying input bytes.
     ///
     /// ### Example
@@ -270,45 +555,180 @@ impl<'r> Deserializer<'r> {
     }
 
     fn peek_field(&mut self) -> Option<&FieldSet> {
-        self.fields.peek()
+        self.fields.peek().map(|fields| &**fields)
     }
 
     fn skip_field(&mut self) {
         self.fields.next();
     }
 
-    fn peek_bytes(&mut self) -> Result<&'r [u8], DeserializeError> {
+    fn peek_item(&mut self) -> Result<(Cow<'r, FieldConfig>, &'r [u8]), DeserializeError> {
         let field = match self.fields.peek() {
-            Some(FieldSet::Item(conf)) => conf,
-            Some(_) => return Err(DeserializeError::UnexpectedEndOfRecord),
-            None => return Err(DeserializeError::UnexpectedEndOfRecord),
+            Some(Cow::Borrowed(fields)) => item(Cow::Borrowed(*fields)),
+            Some(Cow::Owned(FieldSet::Item(conf))) => Some(Cow::Owned(conf.clone())),
+            Some(_) | None => None,
         };
+        let field = field.ok_or(DeserializeError::UnexpectedEndOfRecord)?;
 
+        self.field = Some(context(&field));
         match self.input.get(field.range.clone()) {
-            Some(bytes) => Ok(bytes),
+            Some(bytes) => Ok((field, bytes)),
             None => Err(DeserializeError::UnexpectedEndOfRecord),
         }
     }
 
-    fn next_bytes(&mut self) -> Result<&'r [u8], DeserializeError> {
-        let field = match self.fields.next() {
-            Some(FieldSet::Item(conf)) => conf,
-            Some(_) => return Err(DeserializeError::UnexpectedEndOfRecord),
-            None => return Err(DeserializeError::UnexpectedEndOfRecord),
-        };
+    fn next_item(&mut self) -> Result<(Cow<'r, FieldConfig>, &'r [u8]), DeserializeError> {
+        let field = self
+            .fields
+            .next()
+            .and_then(item)
+            .ok_or(DeserializeError::UnexpectedEndOfRecord)?;
 
-        match self.input.get(field.range) {
-            Some(bytes) => Ok(bytes),
+        self.field = Some(context(&field));
+        match self.input.get(field.range.clone()) {
+            Some(bytes) => Ok((field, bytes)),
             None => Err(DeserializeError::UnexpectedEndOfRecord),
         }
     }
 
-    fn peek_str(&mut self) -> Result<&'r str, DeserializeError> {
-        Ok(str::from_utf8(self.peek_bytes()?)?.trim())
+    // Reads a nested set of fields with a deserializer of its own, keeping its field errors.
+    fn nested<T, F>(&mut self, fields: Cow<'r, FieldSet>, read: F) -> Result<T, DeserializeError>
+    where
+        F: FnOnce(&mut Deserializer<'r>) -> Result<T, DeserializeError>,
+    {
+        let mut de = Deserializer::from_fields(self.input, fields)
+            .encoding(self.encoding)
+            .lenient(self.lenient);
+        let value = read(&mut de);
+
+        self.errors.append(&mut de.errors);
+        value
+    }
+
+    // Reads a single field, adding the field to any error that occurs.
+    fn read_field<T, F>(&mut self, read: F) -> Result<T, DeserializeError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, DeserializeError>,
+    {
+        self.field = None;
+        read(self).map_err(|e| self.field_error(e))
+    }
+
+    // Reads the value of a single field, or `fallback` if it fails in lenient mode.
+    fn read_value<T, F>(&mut self, fallback: T, read: F) -> Result<T, DeserializeError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, DeserializeError>,
+    {
+        match self.read_field(read) {
+            Ok(value) => Ok(value),
+            Err(e) => self.recover(e).map(|_| fallback),
+        }
+    }
+
+    // Keeps the error of a field in lenient mode so that reading can go on. Errors that are not
+    // tied to a field, such as running out of fields, cannot be recovered from.
+    fn recover(&mut self, error: DeserializeError) -> Result<(), DeserializeError> {
+        match error {
+            DeserializeError::Field { .. } if self.lenient => {
+                self.errors.push(error);
+                Ok(())
+            }
+            error => Err(error),
+        }
+    }
+
+    fn field_error(&self, error: DeserializeError) -> DeserializeError {
+        let (name, range) = match (&error, &self.field) {
+            (DeserializeError::Field { .. }, _) | (_, None) => return error,
+            (_, Some(field)) => field,
+        };
+        let end = range.end.min(self.input.len());
+        let bytes = self.input.get(range.start..end).unwrap_or_default();
+
+        DeserializeError::Field {
+            name: name.as_ref().map(|name| name.to_string()),
+            range: range.clone(),
+            bytes: bytes.to_vec(),
+            error: Box::new(error),
+        }
+    }
+
+    fn next_bytes(&mut self) -> Result<&'r [u8], DeserializeError> {
+        self.next_item().map(|(_, bytes)| bytes)
+    }
+
+    fn next_str(&mut self) -> Result<Cow<'r, str>, DeserializeError> {
+        let (field, bytes) = self.next_item()?;
+        self.decode(&field, bytes)
+    }
+
+    fn next_integer(&mut self) -> Result<Cow<'r, str>, DeserializeError> {
+        let (field, bytes) = self.next_item()?;
+        let text = zero_padded(&field, bytes, self.decode(&field, bytes)?);
+
+        if field.scale == 0 {
+            return Ok(trim_whitespace(text));
+        }
+
+        numeric::integer_part(&text).map(|s| Cow::Owned(s.to_string()))
+    }
+
+    fn next_float(&mut self) -> Result<Cow<'r, str>, DeserializeError> {
+        let (field, bytes) = self.next_item()?;
+        let text = self.decode(&field, bytes)?;
+        Ok(trim_whitespace(zero_padded(&field, bytes, text)))
     }
 
-    fn next_str(&mut self) -> Result<&'r str, DeserializeError> {
-        Ok(str::from_utf8(self.next_bytes()?)?.trim())
+    fn decode(
+        &self,
+        field: &FieldConfig,
+        bytes: &'r [u8],
+    ) -> Result<Cow<'r, str>, DeserializeError> {
+        let text = match (field.codec, field.sign) {
+            (Codec::Text, Sign::Inline) => self.decode_codec(field, bytes)?,
+            (Codec::Text, sign) => {
+                let text = numeric::unplace_sign(self.encoding.decode(bytes)?.trim(), sign);
+                Cow::Owned(match text.strip_prefix('-') {
+                    Some(unsigned) => format!("-{}", trim(field, unsigned)),
+                    None => trim(field, &text).to_string(),
+                })
+            }
+            _ => self.decode_codec(field, bytes)?,
+        };
+
+        #[cfg(feature = "chrono")]
+        if let Some(ref format) = field.format {
+            return date::parse(format, &text).map(Cow::Owned);
+        }
+
+        // A field with an implied decimal point is always a number.
+        match field.scale {
+            0 => Ok(text),
+            scale => numeric::scale(&zero_padded(field, bytes, text), scale).map(Cow::Owned),
+        }
+    }
+
+    fn decode_codec(
+        &self,
+        field: &FieldConfig,
+        bytes: &'r [u8],
+    ) -> Result<Cow<'r, str>, DeserializeError> {
+        match field.codec {
+            Codec::Text => {}
+            Codec::Overpunch => {
+                let text = self.encoding.decode(bytes)?;
+                return match text.trim() {
+                    "" => Ok(Cow::Owned(String::new())),
+                    text => numeric::unpunch(text, field.sign == Sign::Leading).map(Cow::Owned),
+                };
+            }
+            ref codec => return numeric::decode(codec, bytes).map(Cow::Owned),
+        }
+
+        Ok(match self.encoding.decode(bytes)? {
+            Cow::Borrowed(s) => Cow::Borrowed(trim(field, s)),
+            Cow::Owned(s) => Cow::Owned(trim(field, &s).to_string()),
+        })
     }
 
     fn done(&mut self) -> bool {
@@ -316,15 +736,95 @@ impl<'r> Deserializer<'r> {
     }
 }
 
+// The fields of a deserializer, owned or borrowed from field definitions that outlive it.
+enum Fields<'r> {
+    Owned(vec::IntoIter<FieldSet>),
+    Borrowed(slice::Iter<'r, FieldSet>),
+}
+
+impl<'r> Fields<'r> {
+    fn new(fields: Cow<'r, FieldSet>) -> Self {
+        match fields {
+            Cow::Owned(fields) => Fields::Owned(fields.into_iter()),
+            Cow::Borrowed(FieldSet::Seq(seq)) => Fields::Borrowed(seq.iter()),
+            Cow::Borrowed(field) => Fields::Borrowed(slice::from_ref(field).iter()),
+        }
+    }
+}
+
+impl<'r> Iterator for Fields<'r> {
+    type Item = Cow<'r, FieldSet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Fields::Owned(fields) => fields.next().map(Cow::Owned),
+            Fields::Borrowed(fields) => fields.next().map(Cow::Borrowed),
+        }
+    }
+}
+
+// The config of a single field, or `None` for a nested set of fields.
+fn item(fields: Cow<'_, FieldSet>) -> Option<Cow<'_, FieldConfig>> {
+    match fields {
+        Cow::Owned(FieldSet::Item(conf)) => Some(Cow::Owned(conf)),
+        Cow::Borrowed(FieldSet::Item(conf)) => Some(Cow::Borrowed(conf)),
+        _ => None,
+    }
+}
+
+// The name and range of a field, borrowed from the field definitions when they are borrowed.
+fn context<'r>(field: &Cow<'r, FieldConfig>) -> (Option<Cow<'r, str>>, Range<usize>) {
+    let name = match field {
+        Cow::Borrowed(conf) => conf.name.as_deref().map(Cow::Borrowed),
+        Cow::Owned(conf) => conf.name.clone().map(Cow::Owned),
+    };
+    (name, field.range.clone())
+}
+
+/// Removes the padding of a field, the mirror of how `ser::pad` adds it.
+fn trim<'a>(field: &FieldConfig, text: &'a str) -> &'a str {
+    let pad = field.pad_with;
+    match field.trim {
+        Trim::Justified => match field.justify {
+            Justify::Left => text.trim_end_matches(pad),
+            Justify::Right => text.trim_start_matches(pad),
+        },
+        Trim::Pad => text.trim_matches(pad),
+        Trim::Whitespace => text.trim(),
+        Trim::Raw => text,
+    }
+}
+
+// A number padded with zeros, such as `0000`, is still a number.
+fn zero_padded<'a>(field: &FieldConfig, bytes: &[u8], text: Cow<'a, str>) -> Cow<'a, str> {
+    let padded = matches!(field.trim, Trim::Justified | Trim::Pad) && field.codec == Codec::Text;
+    // An all zero date is blank, see `date::parse`.
+    #[cfg(feature = "chrono")]
+    let padded = padded && field.format.is_none();
+
+    match text.is_empty() && padded && field.pad_with.is_ascii_digit() && !bytes.is_empty() {
+        true => Cow::Owned(field.pad_with.to_string()),
+        false => text,
+    }
+}
+
+fn trim_whitespace(text: Cow<'_, str>) -> Cow<'_, str> {
+    match text {
+        Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+        Cow::Owned(s) => Cow::Owned(s.trim().to_string()),
+    }
+}
+
 macro_rules! deserialize_int {
     ($de_fn:ident, $visit_fn:ident) => {
         fn $de_fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-            let i = self
-                .next_str()?
-                .parse()
-                .map_err(DeserializeError::ParseIntError)?;
+            let i = self.read_value(0, |de| {
+                de.next_integer()?
+                    .parse()
+                    .map_err(DeserializeError::ParseIntError)
+            })?;
 
-            visitor.$visit_fn(i)
+            visitor.$visit_fn(i).map_err(|e| self.field_error(e))
         }
     };
 }
@@ -333,84 +833,128 @@ impl<'a, 'de: 'a> serde::Deserializer<'de> for &'a mut Deserializer<'de> {
     type Error = DeserializeError;
 
     fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let s = self.next_str()?;
-        if s.len() > 1 {
-            Err(DeserializeError::Message(format!(
-                "expected bool field to be 1 byte, got {}",
-                s.len()
-            )))
-        } else {
-            let c = s.chars().next().unwrap_or('0');
-            if c == '0' {
-                visitor.visit_bool(false)
+        let b = self.read_value(false, |de| {
+            let (field, bytes) = de.next_item()?;
+            let text = de.decode(&field, bytes)?;
+            let s = text.trim();
+
+            if let Some(ref booleans) = field.booleans {
+                return booleans
+                    .read(s)
+                    .ok_or_else(|| DeserializeError::InvalidBool(s.to_string(), booleans.clone()));
+            }
+
+            if s.len() > 1 {
+                Err(DeserializeError::Message(format!(
+                    "expected bool field to be 1 byte, got {}",
+                    s.len()
+                )))
             } else {
-                visitor.visit_bool(true)
+                Ok(s.chars().next().unwrap_or('0') != '0')
             }
-        }
+        })?;
+
+        visitor.visit_bool(b).map_err(|e| self.field_error(e))
     }
 
     deserialize_int!(deserialize_i8, visit_i8);
     deserialize_int!(deserialize_i16, visit_i16);
     deserialize_int!(deserialize_i32, visit_i32);
     deserialize_int!(deserialize_i64, visit_i64);
+    deserialize_int!(deserialize_i128, visit_i128);
     deserialize_int!(deserialize_u8, visit_u8);
     deserialize_int!(deserialize_u16, visit_u16);
     deserialize_int!(deserialize_u32, visit_u32);
     deserialize_int!(deserialize_u64, visit_u64);
+    deserialize_int!(deserialize_u128, visit_u128);
 
     fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let f = self
-            .next_str()?
-            .parse()
-            .map_err(DeserializeError::ParseFloatError)?;
+        let f = self.read_value(0.0, |de| {
+            de.next_float()?
+                .parse()
+                .map_err(DeserializeError::ParseFloatError)
+        })?;
 
-        visitor.visit_f32(f)
+        visitor.visit_f32(f).map_err(|e| self.field_error(e))
     }
 
     fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let f = self
-            .next_str()?
-            .parse()
-            .map_err(DeserializeError::ParseFloatError)?;
+        let f = self.read_value(0.0, |de| {
+            de.next_float()?
+                .parse()
+                .map_err(DeserializeError::ParseFloatError)
+        })?;
 
-        visitor.visit_f64(f)
+        visitor.visit_f64(f).map_err(|e| self.field_error(e))
     }
 
     fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        self.next_str().and_then(|s| visitor.visit_borrowed_str(s))
+        let value = match self.read_value(Cow::Borrowed(""), |de| de.next_str())? {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        };
+
+        value.map_err(|e| self.field_error(e))
     }
 
     fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        self.next_str().and_then(|s| visitor.visit_borrowed_str(s))
+        self.deserialize_str(visitor)
     }
 
     fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let s = self.next_str()?;
-        if s.len() > 1 {
-            Err(DeserializeError::Message(format!(
-                "expected bool field to be 1 byte, got {}",
-                s.len()
-            )))
-        } else {
-            let c = s.chars().next().unwrap_or(' ');
-            visitor.visit_char(c)
-        }
+        let c = self.read_value(' ', |de| {
+            let s = de.next_str()?;
+            if s.chars().count() > 1 {
+                Err(DeserializeError::Message(format!(
+                    "expected char field to be 1 character, got {}",
+                    s.chars().count()
+                )))
+            } else {
+                Ok(s.chars().next().unwrap_or(' '))
+            }
+        })?;
+
+        visitor.visit_char(c).map_err(|e| self.field_error(e))
     }
 
     fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        self.next_bytes()
-            .and_then(|b| visitor.visit_borrowed_bytes(b))
+        let b = self.read_value(&[][..], |de| de.next_bytes())?;
+        visitor
+            .visit_borrowed_bytes(b)
+            .map_err(|e| self.field_error(e))
     }
 
     fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        self.next_bytes()
-            .and_then(|b| visitor.visit_byte_buf(b.to_vec()))
+        let b = self.read_value(&[][..], |de| de.next_bytes())?;
+        visitor
+            .visit_byte_buf(b.to_vec())
+            .map_err(|e| self.field_error(e))
     }
 
     fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        if self.peek_str()?.is_empty() {
+        let checked = self.read_field(|de| {
+            let (field, bytes) = de.peek_item()?;
+            let is_null = field
+                .null
+                .as_ref()
+                .is_some_and(|null| null.matches(bytes, de.encoding));
+
+            if is_null {
+                return Ok(true);
+            }
+
+            let text = zero_padded(&field, bytes, de.decode(&field, bytes)?);
+            Ok(text.trim().is_empty())
+        });
+        // A field that cannot be checked is read as `None` in lenient mode.
+        let is_none = match checked {
+            Ok(is_none) => is_none,
+            Err(e) => self.recover(e).map(|_| true)?,
+        };
+
+        if is_none {
             self.skip_field();
-            visitor.visit_none()
+            visitor.visit_none().map_err(|e| self.field_error(e))
         } else {
             visitor.visit_some(self)
         }
@@ -506,11 +1050,11 @@ impl<'a, 'de: 'a> de::SeqAccess<'de> for &'a mut Deserializer<'de> {
         &mut self,
         seed: S,
     ) -> Result<Option<S::Value>, Self::Error> {
-        match self.fields.peek() {
+        match self.peek_field() {
             Some(FieldSet::Item(_)) => seed.deserialize(&mut **self).map(Some),
             Some(FieldSet::Seq(_)) => {
-                let mut de = Deserializer::new(self.input, self.fields.next().unwrap());
-                seed.deserialize(&mut de).map(Some)
+                let fields = self.fields.next().unwrap();
+                self.nested(fields, |de| seed.deserialize(de)).map(Some)
             }
             None => Ok(None),
         }
@@ -547,20 +1091,70 @@ impl<'a, 'de: 'a> de::MapAccess<'de> for &'a mut Deserializer<'de> {
     }
 }
 
+/// The variant of an enum being read, with the fields of its layout if the enum is tagged. The
+/// fields of an untagged variant follow the field holding the name of the variant.
+pub struct EnumVariant<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    fields: Option<Cow<'de, FieldSet>>,
+}
+
+impl<'a, 'de: 'a> EnumVariant<'a, 'de> {
+    fn nested<T, F>(self, read: F) -> Result<T, DeserializeError>
+    where
+        F: FnOnce(&mut Deserializer<'de>) -> Result<T, DeserializeError>,
+    {
+        let fields = self
+            .fields
+            .unwrap_or_else(|| Cow::Owned(FieldSet::Seq(vec![])));
+        self.de.nested(fields, read)
+    }
+}
+
 impl<'a, 'de: 'a> de::EnumAccess<'de> for &'a mut Deserializer<'de> {
     type Error = DeserializeError;
-    type Variant = Self;
+    type Variant = EnumVariant<'a, 'de>;
 
     fn variant_seed<S: de::DeserializeSeed<'de>>(
         self,
         seed: S,
     ) -> Result<(S::Value, Self::Variant), Self::Error> {
-        seed.deserialize(self.next_str()?.into_deserializer())
-            .map(|v| (v, self))
+        let (value, fields) = self.read_field(|de| {
+            let (field, bytes) = de.peek_item()?;
+
+            if field.variants.is_empty() {
+                let variant = de.next_str()?;
+                let name: StrDeserializer<DeserializeError> = variant.trim().into_deserializer();
+                let value = seed.deserialize(name)?;
+                return Ok((value, None));
+            }
+
+            let tag = de.decode(&field, bytes)?;
+            let tag = tag.trim();
+            let (name, fields) = match field {
+                Cow::Borrowed(conf) => conf
+                    .variants
+                    .iter()
+                    .find(|v| v.tag.trim() == tag)
+                    .map(|v| (v.name.clone(), Cow::Borrowed(&v.fields))),
+                Cow::Owned(conf) => conf
+                    .variants
+                    .into_iter()
+                    .find(|v| v.tag.trim() == tag)
+                    .map(|v| (v.name, Cow::Owned(v.fields))),
+            }
+            .ok_or_else(|| DeserializeError::UnknownRecordType(tag.to_string()))?;
+            de.skip_field();
+
+            let name: StrDeserializer<DeserializeError> = name.as_str().into_deserializer();
+            let value = seed.deserialize(name)?;
+            Ok((value, Some(fields)))
+        })?;
+
+        Ok((value, EnumVariant { de: self, fields }))
     }
 }
 
-impl<'a, 'de: 'a> de::VariantAccess<'de> for &'a mut Deserializer<'de> {
+impl<'a, 'de: 'a> de::VariantAccess<'de> for EnumVariant<'a, 'de> {
     type Error = DeserializeError;
 
     fn unit_variant(self) -> Result<(), Self::Error> {
@@ -569,34 +1163,37 @@ impl<'a, 'de: 'a> de::VariantAccess<'de> for &'a mut Deserializer<'de> {
 
     fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
         self,
-        _seed: T,
+        seed: T,
     ) -> Result<T::Value, Self::Error> {
-        Err(DeserializeError::invalid_type(
-            de::Unexpected::UnitVariant,
-            &"newtype variant",
-        ))
+        match self.fields {
+            Some(_) => self.nested(|de| seed.deserialize(de)),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
     }
 
     fn tuple_variant<V: Visitor<'de>>(
         self,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        Err(DeserializeError::invalid_type(
-            de::Unexpected::UnitVariant,
-            &"tuple variant",
-        ))
+        match self.fields {
+            Some(_) => self.nested(|de| visitor.visit_seq(de)),
+            None => visitor.visit_seq(self.de),
+        }
     }
 
     fn struct_variant<V: Visitor<'de>>(
         self,
         _fields: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        Err(DeserializeError::invalid_type(
-            de::Unexpected::UnitVariant,
-            &"struct variant",
-        ))
+        match self.fields {
+            Some(_) => self.nested(|de| visitor.visit_seq(de)),
+            None => visitor.visit_seq(self.de),
+        }
     }
 }
 
@@ -882,7 +1479,7 @@ mod test {
         assert_eq!(test.get("a").unwrap(), "123");
         assert_eq!(test.get("b").unwrap(), "abc");
         assert_eq!(test.get("6..10").unwrap(), "9876");
-        assert_eq!(test.get("d").unwrap(), "12");
+        assert_eq!(test.get("d").unwrap(), " 12");
     }
 
     #[derive(Debug, PartialEq, Deserialize)]
@@ -897,6 +1494,81 @@ mod test {
         assert_eq!(e, Enum::Foo);
     }
 
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Tagged {
+        Header { date: String },
+        Detail(String, u32),
+        Total(u32),
+        Trailer,
+    }
+
+    fn tagged_fields() -> FieldSet {
+        FieldSet::new_field(0..1)
+            .variant(
+                "H",
+                "Header",
+                FieldSet::Seq(vec![FieldSet::new_field(1..9)]),
+            )
+            .variant(
+                "D",
+                "Detail",
+                FieldSet::Seq(vec![FieldSet::new_field(1..5), FieldSet::new_field(5..9)]),
+            )
+            .variant("S", "Total", FieldSet::new_field(1..9))
+            .variant("T", "Trailer", FieldSet::Seq(vec![]))
+    }
+
+    #[test]
+    fn tagged_enum_de() {
+        let read = |s: &str| from_str_with_fields::<Tagged>(s, tagged_fields());
+
+        assert_eq!(
+            read("H20240131").unwrap(),
+            Tagged::Header {
+                date: "20240131".to_string()
+            }
+        );
+        assert_eq!(
+            read("DAnn 0012").unwrap(),
+            Tagged::Detail("Ann".to_string(), 12)
+        );
+        assert_eq!(read("S00000042").unwrap(), Tagged::Total(42));
+        assert_eq!(read("T        ").unwrap(), Tagged::Trailer);
+
+        match read("X12345678") {
+            Err(error::Error::DeserializeError(e)) => match e.without_context() {
+                DeserializeError::UnknownRecordType(tag) => assert_eq!(tag, "X"),
+                other => panic!("expected UnknownRecordType, got {:?}", other),
+            },
+            other => panic!("expected UnknownRecordType, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn borrowed_fields_de() {
+        let fields = FieldSet::Seq(vec![
+            tagged_fields(),
+            FieldSet::Seq(vec![
+                FieldSet::new_field(9..10),
+                FieldSet::new_field(10..12),
+            ]),
+        ]);
+        let read = |s: &'static str| {
+            let mut de = Deserializer::with_fields(s.as_bytes(), &fields);
+            <(Tagged, (String, u8))>::deserialize(&mut de)
+        };
+
+        assert_eq!(
+            read("DAnn 0012x34").unwrap(),
+            (Tagged::Detail("Ann".to_string(), 12), ("x".to_string(), 34))
+        );
+        assert_eq!(
+            read("S00000042y56").unwrap(),
+            (Tagged::Total(42), ("y".to_string(), 56))
+        );
+        assert!(read("X12345678z78").is_err());
+    }
+
     #[test]
     fn from_str_de() {
         let s = "123abc9876 12";
@@ -1058,4 +1730,343 @@ mod test {
         assert_eq!(arr[2], Some((253, 254)));
         assert_eq!(arr[3], Some((121, 232)));
     }
+
+    #[test]
+    fn test_encoded_strings() {
+        #[derive(Deserialize)]
+        struct Test<'a> {
+            a: String,
+            b: &'a str,
+            c: u16,
+        }
+
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..5),
+            FieldSet::new_field(5..8),
+            FieldSet::new_field(8..10),
+        ]);
+
+        let input = b"caf\xE9 abc42";
+        let mut de = Deserializer::new(input, fields.clone()).encoding(Encoding::Latin1);
+        let test = Test::deserialize(&mut de).unwrap();
+
+        assert_eq!(test.a, "caf\u{e9}");
+        assert_eq!(test.b, "abc");
+        assert_eq!(test.c, 42);
+
+        // Borrowing is only possible when the field does not need to be transcoded.
+        let input = [0x83, 0x81, 0x86, 0x51, 0x40, 0x81, 0x82, 0x83, 0xF4, 0xF2];
+        let mut de = Deserializer::new(&input, fields).encoding(Encoding::Cp037);
+        assert!(Test::deserialize(&mut de).is_err());
+    }
+
+    #[test]
+    fn test_encoding_leaves_bytes_untouched() {
+        let fields = FieldSet::Seq(vec![FieldSet::new_field(0..2), FieldSet::new_field(2..4)]);
+        let input = [0xC1, 0xC2, 0x12, 0x3C];
+
+        let mut de = Deserializer::new(&input, fields).encoding(Encoding::Cp037);
+        let (s, b) = <(String, ByteBuf)>::deserialize(&mut de).unwrap();
+
+        assert_eq!(s, "AB");
+        assert_eq!(b.into_vec(), vec![0x12, 0x3C]);
+    }
+
+    #[test]
+    fn test_numeric_codecs() {
+        #[derive(Debug, Deserialize)]
+        struct Test {
+            name: String,
+            balance: i32,
+            count: u16,
+            rate: f64,
+        }
+
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2).name("name"),
+            FieldSet::new_field(2..5)
+                .name("balance")
+                .codec(Codec::Packed { signed: true }),
+            FieldSet::new_field(5..7)
+                .name("count")
+                .codec(Codec::Binary {
+                    endian: crate::Endian::Little,
+                    signed: false,
+                }),
+            FieldSet::new_field(7..9)
+                .name("rate")
+                .codec(Codec::Zoned { signed: true }),
+        ]);
+        let input = [0xC1, 0xC2, 0x01, 0x23, 0x4D, 0x2C, 0x01, 0xF1, 0xD5];
+
+        let mut de = Deserializer::new(&input, fields.clone()).encoding(Encoding::Cp037);
+        let test = Test::deserialize(&mut de).unwrap();
+
+        assert_eq!(test.name, "AB");
+        assert_eq!(test.balance, -1234);
+        assert_eq!(test.count, 300);
+        assert_eq!(test.rate, -15.0);
+
+        let input = [0xC1, 0xC2, 0x0A, 0x23, 0x4C, 0x2C, 0x01, 0xF1, 0xD5];
+        let mut de = Deserializer::new(&input, fields).encoding(Encoding::Cp037);
+
+        match Test::deserialize(&mut de) {
+            Err(DeserializeError::Field { name, error, .. }) => {
+                assert_eq!(name.as_deref(), Some("balance"));
+                assert!(matches!(*error, DeserializeError::InvalidNumber(_)));
+            }
+            r => panic!("expected an invalid number error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_overpunched_numbers() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).codec(Codec::Overpunch),
+            FieldSet::new_field(4..8).codec(Codec::Overpunch),
+        ]);
+
+        let (a, b): (i32, f64) = from_str_with_fields("012C045}", fields.clone()).unwrap();
+        assert_eq!((a, b), (123, -450.0));
+
+        let input = [0xF0, 0xF1, 0xF2, 0xC3, 0xF0, 0xF4, 0xF5, 0xD0];
+        let mut de = Deserializer::new(&input, fields.clone()).encoding(Encoding::Cp037);
+        let (a, b) = <(i32, f64)>::deserialize(&mut de).unwrap();
+        assert_eq!((a, b), (123, -450.0));
+
+        match from_str_with_fields::<(i32, i32)>("012S0000", fields) {
+            Err(crate::Error::DeserializeError(e)) => {
+                assert!(matches!(
+                    e.without_context(),
+                    DeserializeError::InvalidNumber(_)
+                ))
+            }
+            r => panic!("expected an invalid number error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_128_bit_integers() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..40),
+            FieldSet::new_field(40..80),
+        ]);
+        let s = format!("{:<40}{:<40}", i128::MIN, u128::MAX);
+
+        let (a, b): (i128, u128) = from_str_with_fields(&s, fields).unwrap();
+        assert_eq!((a, b), (i128::MIN, u128::MAX));
+    }
+
+    #[test]
+    fn test_sign_placement() {
+        let cases = [
+            (Sign::Leading, "-  12  012"),
+            (Sign::Trailing, "0012-00012"),
+            (Sign::Separate, "0012-0012+"),
+            (Sign::Always, "-0012+0012"),
+        ];
+
+        for (sign, s) in cases.iter() {
+            let fields = FieldSet::Seq(vec![FieldSet::new_field(0..5), FieldSet::new_field(5..10)])
+                .sign(*sign);
+            let (a, b): (i32, u32) = from_str_with_fields(s, fields).unwrap();
+
+            assert_eq!((a, b), (-12, 12));
+        }
+
+        let fields = FieldSet::new_field(0..6).sign(Sign::Trailing).scale(2);
+        let f: f64 = from_str_with_fields("01250-", fields).unwrap();
+        assert_eq!(f, -12.5);
+    }
+
+    #[test]
+    fn test_field_context() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..3).name("id"),
+            FieldSet::Seq(vec![
+                FieldSet::new_field(3..5).name("count"),
+                FieldSet::new_field(5..9),
+            ]),
+        ]);
+
+        let err = from_str_with_fields::<(u8, (u8, u8))>("00112345", fields.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "field 5..9 at column 6, `345`: byte length of record was less than defined length"
+        );
+
+        match from_str_with_fields::<(u8, (u8, u8))>("0011x5678", fields) {
+            Err(error::Error::DeserializeError(DeserializeError::Field {
+                name,
+                range,
+                bytes,
+                error,
+            })) => {
+                assert_eq!(name.as_deref(), Some("count"));
+                assert_eq!(range, 3..5);
+                assert_eq!(bytes, b"1x");
+                assert!(matches!(*error, DeserializeError::ParseIntError(_)));
+            }
+            r => panic!("expected an error in the count field, got {:?}", r),
+        }
+
+        let fields = FieldSet::new_field(0..2).codec(Codec::Packed { signed: true });
+        let err = from_bytes_with_fields::<u8>(&[0x1A, 0x2B], fields).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("field 0..2 at column 1, [1A 2B]: "));
+    }
+
+    #[test]
+    fn test_lenient() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2).name("a"),
+            FieldSet::Seq(vec![
+                FieldSet::new_field(2..4).name("b"),
+                FieldSet::new_field(4..6).name("c"),
+            ]),
+            FieldSet::new_field(6..8).name("d"),
+            FieldSet::new_field(8..9).name("e"),
+        ]);
+        let read = |s: &'static str| {
+            let mut de = Deserializer::new(s.as_bytes(), fields.clone()).lenient(true);
+            let value = <(u8, (u8, f32), Option<u8>, bool)>::deserialize(&mut de);
+            (value, de.into_errors())
+        };
+
+        let (value, errors) = read("01x2zz  1");
+        assert_eq!(value.unwrap(), (1, (0, 0.0), None, true));
+
+        let names: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                DeserializeError::Field { name, .. } => name.clone().unwrap(),
+                e => panic!("expected a field error, got {:?}", e),
+            })
+            .collect();
+        assert_eq!(names, vec!["b", "c"]);
+
+        let (value, errors) = read("0102zzxx0");
+        assert_eq!(value.unwrap(), (1, (2, 0.0), Some(0), false));
+        assert_eq!(errors.len(), 2);
+
+        // Fields past the end of the record are invalid, and read as `None` if optional.
+        let (value, errors) = read("01x");
+        assert_eq!(value.unwrap(), (1, (0, 0.0), None, false));
+        assert_eq!(errors.len(), 4);
+
+        // Running out of fields is not an error of a single field.
+        let mut de = Deserializer::new(b"01x2zz110", fields).lenient(true);
+        assert!(<(u8, (u8, u8), u8, u8, u8)>::deserialize(&mut de).is_err());
+        assert_eq!(de.errors().len(), 2);
+
+        let mut de = Deserializer::new(b"01x2", FieldSet::new_field(0..4)).lenient(false);
+        assert!(u8::deserialize(&mut de).is_err());
+        assert!(de.errors().is_empty());
+    }
+
+    #[test]
+    fn test_boolean_mappings() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..1).booleans(Booleans::yes_no()),
+            FieldSet::new_field(1..2).booleans(Booleans::true_false().blank(false)),
+            FieldSet::new_field(2..3).booleans(Booleans::flag("X")),
+            FieldSet::new_field(3..6).booleans(Booleans::new("YES", "NO")),
+        ]);
+
+        let t: (bool, bool, bool, bool) = from_str_with_fields("YTXYES", fields.clone()).unwrap();
+        let f: (bool, bool, bool, bool) = from_str_with_fields("N   NO", fields.clone()).unwrap();
+
+        assert_eq!(t, (true, true, true, true));
+        assert_eq!(f, (false, false, false, false));
+
+        match from_str_with_fields::<(bool, bool, bool, bool)>("1TXYES", fields.clone()) {
+            Err(crate::Error::DeserializeError(e)) => match e.without_context() {
+                DeserializeError::InvalidBool(s, _) => assert_eq!(s, "1"),
+                other => panic!("expected an invalid bool error, got {:?}", other),
+            },
+            r => panic!("expected an invalid bool error, got {:?}", r),
+        }
+
+        assert!(from_str_with_fields::<(bool, bool, bool, bool)>(" TXYES", fields).is_err());
+    }
+
+    #[test]
+    fn test_trim() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..6).pad_with('*').trim(Trim::Pad),
+            FieldSet::new_field(6..11)
+                .pad_with('0')
+                .sign(Sign::Trailing),
+            FieldSet::new_field(11..15).trim(Trim::Whitespace),
+            FieldSet::new_field(15..19),
+        ]);
+        let input = b"**ab**1200- 7  cd  ";
+        let mut de = Deserializer::new(input, fields);
+        let values: (String, i32, u8, String) = Deserialize::deserialize(&mut de).unwrap();
+
+        assert_eq!(values, ("ab".to_string(), -12, 7, "cd".to_string()));
+    }
+
+    #[test]
+    fn test_trim_zero_padded() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).pad_with('0'),
+            FieldSet::new_field(4..8)
+                .pad_with('0')
+                .justify(Justify::Right),
+            FieldSet::new_field(8..12)
+                .pad_with('0')
+                .justify(Justify::Right),
+        ]);
+        let mut de = Deserializer::new(b"000000000000", fields);
+        let values: (String, u32, f64) = Deserialize::deserialize(&mut de).unwrap();
+
+        assert_eq!(values, (String::new(), 0, 0.0));
+    }
+
+    #[test]
+    fn test_zero_padded_option() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..5)
+                .pad_with('0')
+                .justify(Justify::Right),
+            FieldSet::new_field(5..10)
+                .pad_with('0')
+                .justify(Justify::Right)
+                .sign(Sign::Leading),
+        ]);
+        let values: (Option<u32>, Option<i32>) = (Some(0), Some(0));
+
+        let mut wrtr = crate::Writer::from_memory();
+        crate::to_writer_with_fields(&mut wrtr, &values, fields.clone()).unwrap();
+        let s: String = wrtr.into();
+        assert_eq!(s, "0000000000");
+
+        let read: (Option<u32>, Option<i32>) = from_str_with_fields(&s, fields).unwrap();
+        assert_eq!(read, values);
+    }
+
+    #[test]
+    fn test_null_representations() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4)
+                .codec(Codec::Packed { signed: true })
+                .null(crate::Null::Byte(0x00)),
+            FieldSet::new_field(4..8).null(crate::Null::Fill('0')),
+            FieldSet::new_field(8..12).null(crate::Null::Text("NULL".to_string())),
+        ]);
+
+        let input = [0, 0, 0, 0, b'0', b'0', b'0', b'0', b'N', b'U', b'L', b'L'];
+        let (a, b, c): (Option<i32>, Option<u32>, Option<String>) =
+            from_bytes_with_fields(&input, fields.clone()).unwrap();
+        assert_eq!((a, b, c), (None, None, None));
+
+        let input = [
+            0, 0, 0, 0x1C, b'0', b'0', b'4', b'2', b'N', b'U', b'L', b'?',
+        ];
+        let (a, b, c): (Option<i32>, Option<u32>, Option<String>) =
+            from_bytes_with_fields(&input, fields).unwrap();
+        assert_eq!((a, b, c), (Some(1), Some(42), Some("NUL?".to_string())));
+    }
 }
@@ -0,0 +1,209 @@
+/*!
+Reading and writing `rust_decimal::Decimal` values exactly.
+
+This module is only available with the `rust_decimal` feature enabled:
+
+```toml
+fixed_width = { version = "0.6", features = ["rust_decimal"] }
+```
+
+A `Decimal` field goes through the text of its value, so it never passes through `f64`. The
+justification, padding, codec, scale and overflow policy of the field all apply, just like for
+integer fields, so a value too wide for its field is an error rather than being truncated. Use
+these functions with serde's `with` attribute, or `decimal::option` for an `Option<Decimal>`.
+
+```rust
+use fixed_width::{FieldSet, FixedWidth, Justify};
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    #[serde(with = "fixed_width::decimal")]
+    amount: Decimal,
+    #[serde(with = "fixed_width::decimal::option")]
+    fee: Option<Decimal>,
+}
+
+impl FixedWidth for Entry {
+    fn fields() -> FieldSet {
+        FieldSet::Seq(vec![
+            FieldSet::new_field(0..10).scale(2).pad_with('0').justify(Justify::Right),
+            FieldSet::new_field(10..14).scale(2).justify(Justify::Right),
+        ])
+    }
+}
+
+let entry: Entry = fixed_width::from_str("0001234567  50").unwrap();
+assert_eq!(entry.amount, Decimal::from_str("12345.67").unwrap());
+assert_eq!(entry.fee, Some(Decimal::from_str("0.50").unwrap()));
+
+let entry = Entry { amount: Decimal::from_str("0.5").unwrap(), fee: None };
+assert_eq!(fixed_width::to_string(&entry).unwrap(), "0000000050    ");
+```
+*/
+use crate::ser::NUMBER;
+use rust_decimal::Decimal;
+use serde::{
+    de::{self, Deserialize},
+    Deserializer, Serialize, Serializer,
+};
+
+// The text of a decimal, marked as a number for the serializer of this crate. Other serializers
+// write it as a plain string.
+struct Number<'a>(&'a Decimal);
+
+impl Serialize for Number<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(NUMBER, &self.0.to_string())
+    }
+}
+
+/// Serializes a `Decimal` as the text of its value.
+pub fn serialize<S: Serializer>(val: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    Number(val).serialize(serializer)
+}
+
+/// Deserializes a `Decimal` from the text of a field.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse(&s)
+}
+
+// Whitespace is trimmed as for other numbers, since it is kept by `Trim::Raw`, `Trim::Pad` and pad
+// characters other than spaces.
+fn parse<E: de::Error>(s: &str) -> Result<Decimal, E> {
+    let s = s.trim();
+    s.parse()
+        .map_err(|e| E::custom(format!("`{}` is not a valid decimal: {}", s, e)))
+}
+
+/// Reading and writing an `Option<Decimal>`, where an empty field is `None`.
+pub mod option {
+    use super::{parse, Number};
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes an `Option<Decimal>`, writing `None` as an empty field.
+    pub fn serialize<S: Serializer>(
+        val: &Option<Decimal>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match val {
+            Some(d) => serializer.serialize_some(&Number(d)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an `Option<Decimal>`, reading an empty field as `None`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Decimal>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => parse(&s).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{from_bytes_with_fields, Codec, Error, FieldSet, SerializeError, Writer};
+    use rust_decimal::Decimal;
+    use serde_derive::{Deserialize, Serialize};
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Ledger {
+        #[serde(with = "crate::decimal")]
+        total: Decimal,
+        #[serde(with = "crate::decimal::option")]
+        adjustment: Option<Decimal>,
+    }
+
+    fn fields() -> FieldSet {
+        FieldSet::Seq(vec![
+            FieldSet::new_field(0..4)
+                .codec(Codec::Packed { signed: true })
+                .scale(3),
+            FieldSet::new_field(4..10).codec(Codec::Overpunch).scale(2),
+        ])
+    }
+
+    #[test]
+    fn decimal_round_trip() {
+        let bytes = [0x12, 0x34, 0x56, 0x7D, b'0', b'0', b'0', b'1', b'2', b'E'];
+        let ledger: Ledger = from_bytes_with_fields(&bytes, fields()).unwrap();
+
+        assert_eq!(ledger.total, Decimal::from_str("-1234.567").unwrap());
+        assert_eq!(ledger.adjustment, Some(Decimal::from_str("1.25").unwrap()));
+
+        let mut wrtr = Writer::from_memory();
+        crate::to_writer_with_fields(&mut wrtr, &ledger, fields()).unwrap();
+        assert_eq!(Into::<Vec<u8>>::into(wrtr), bytes.to_vec());
+
+        let bytes = [0x00, 0x00, 0x00, 0x0C, b' ', b' ', b' ', b' ', b' ', b' '];
+        let ledger: Ledger = from_bytes_with_fields(&bytes, fields()).unwrap();
+
+        assert_eq!(ledger.total, Decimal::ZERO);
+        assert_eq!(ledger.adjustment, None);
+    }
+
+    #[test]
+    fn decimal_trims_whitespace() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..6).trim(crate::Trim::Raw),
+            FieldSet::new_field(6..12)
+                .pad_with('*')
+                .trim(crate::Trim::Pad),
+        ]);
+
+        let ledger: Ledger = crate::from_str_with_fields("12.5  ** 1.2**", fields).unwrap();
+
+        assert_eq!(ledger.total, Decimal::from_str("12.5").unwrap());
+        assert_eq!(ledger.adjustment, Some(Decimal::from_str("1.2").unwrap()));
+    }
+
+    #[test]
+    fn decimal_refuses_to_round() {
+        let ledger = Ledger {
+            total: Decimal::from_str("1.2345").unwrap(),
+            adjustment: None,
+        };
+
+        let mut wrtr = Writer::from_memory();
+        assert!(crate::to_writer_with_fields(&mut wrtr, &ledger, fields()).is_err());
+    }
+
+    #[test]
+    fn decimal_refuses_to_truncate() {
+        #[derive(Serialize)]
+        struct Amounts {
+            #[serde(with = "crate::decimal")]
+            amount: Decimal,
+            #[serde(with = "crate::decimal::option")]
+            fee: Option<Decimal>,
+        }
+
+        let fields = FieldSet::Seq(vec![FieldSet::new_field(0..5), FieldSet::new_field(5..8)]);
+        let write = |amount: &str, fee: &str| {
+            let amounts = Amounts {
+                amount: Decimal::from_str(amount).unwrap(),
+                fee: Some(Decimal::from_str(fee).unwrap()),
+            };
+            let mut wrtr = Writer::from_memory();
+            crate::to_writer_with_fields(&mut wrtr, &amounts, fields.clone())
+                .map(|_| String::from(wrtr))
+        };
+
+        assert_eq!(write("123.4", "1.5").unwrap(), "123.41.5");
+
+        for (amount, fee) in [("12345.67", "1.5"), ("1.5", "12.50")] {
+            match write(amount, fee) {
+                Err(Error::SerializeError(SerializeError::FieldOverflow(..))) => {}
+                r => panic!("expected a field overflow, got {:?}", r),
+            }
+        }
+    }
+}
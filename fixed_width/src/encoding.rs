@@ -0,0 +1,278 @@
+use crate::ser::SerializeError;
+use std::{borrow::Cow, convert::TryFrom, fmt, str};
+
+/// The character encoding of the text in a record.
+///
+/// Field ranges are always measured in bytes of the encoded data, so a field of 10 bytes in an
+/// EBCDIC file holds 10 characters. Only text is transcoded: values read with
+/// `deserialize_bytes`, such as `serde_bytes::ByteBuf`, are passed through untouched.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::Encoding;
+///
+/// let bytes = Encoding::Cp037.encode("HELLO").unwrap();
+/// assert_eq!(&bytes[..], &[0xC8, 0xC5, 0xD3, 0xD3, 0xD6]);
+///
+/// let s = Encoding::Cp037.decode(&bytes).unwrap();
+/// assert_eq!(s, "HELLO");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Encoding {
+    /// UTF-8. This is the default.
+    #[default]
+    Utf8,
+    /// ISO-8859-1, where every byte is the Unicode code point of the same value.
+    Latin1,
+    /// Windows-1252, Latin-1 with printable characters in place of most C1 control codes.
+    Windows1252,
+    /// EBCDIC code page 037, used for US and Canadian English.
+    Cp037,
+    /// EBCDIC code page 500, International Latin-1.
+    Cp500,
+    /// EBCDIC code page 1047, Latin-1 as used by z/OS Unix System Services.
+    Cp1047,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Windows1252 => "Windows-1252",
+            Encoding::Cp037 => "cp037",
+            Encoding::Cp500 => "cp500",
+            Encoding::Cp1047 => "cp1047",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Encoding {
+    /// Decodes bytes in this encoding into a string. Data that is already valid UTF-8 is
+    /// borrowed rather than copied.
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, str::Utf8Error> {
+        match self {
+            Encoding::Utf8 => str::from_utf8(bytes).map(Cow::Borrowed),
+            Encoding::Latin1 | Encoding::Windows1252 if bytes.is_ascii() => {
+                // ASCII is valid UTF-8, so this can not fail.
+                str::from_utf8(bytes).map(Cow::Borrowed)
+            }
+            _ => Ok(Cow::Owned(
+                bytes.iter().map(|&b| self.decode_byte(b)).collect(),
+            )),
+        }
+    }
+
+    /// Encodes a string into bytes in this encoding. Returns an error if the string contains a
+    /// character that the encoding can not represent.
+    pub fn encode<'a>(&self, s: &'a str) -> Result<Cow<'a, [u8]>, SerializeError> {
+        match self {
+            Encoding::Utf8 => Ok(Cow::Borrowed(s.as_bytes())),
+            Encoding::Latin1 | Encoding::Windows1252 if s.is_ascii() => {
+                Ok(Cow::Borrowed(s.as_bytes()))
+            }
+            _ => s
+                .chars()
+                .map(|c| {
+                    self.encode_char(c)
+                        .ok_or(SerializeError::Unencodable(c, *self))
+                })
+                .collect::<Result<Vec<u8>, _>>()
+                .map(Cow::Owned),
+        }
+    }
+
+    /// Returns `true` if every character is encoded as a single byte.
+    pub fn is_single_byte(&self) -> bool {
+        !matches!(self, Encoding::Utf8)
+    }
+
+    /// Returns `true` if a character of the encoded `bytes` starts at `index`, or if `index` is
+    /// their end.
+    pub(crate) fn is_char_boundary(&self, bytes: &[u8], index: usize) -> bool {
+        match bytes.get(index) {
+            Some(&b) => self.is_single_byte() || b & 0xC0 != 0x80,
+            None => index == bytes.len(),
+        }
+    }
+
+    fn decode_byte(&self, b: u8) -> char {
+        match self {
+            Encoding::Utf8 | Encoding::Latin1 => char::from(b),
+            Encoding::Windows1252 => match b {
+                0x80..=0x9F => WINDOWS1252[usize::from(b - 0x80)],
+                _ => char::from(b),
+            },
+            Encoding::Cp037 => CP037[usize::from(b)],
+            Encoding::Cp500 => CP500[usize::from(b)],
+            Encoding::Cp1047 => CP1047[usize::from(b)],
+        }
+    }
+
+    /// Encodes a single character as one byte, for single byte encodings. For UTF-8 only ASCII
+    /// characters can be encoded as one byte.
+    pub(crate) fn encode_char(&self, c: char) -> Option<u8> {
+        let table: &[char; 256] = match self {
+            Encoding::Utf8 => return if c.is_ascii() { Some(c as u8) } else { None },
+            Encoding::Latin1 => return u8::try_from(u32::from(c)).ok(),
+            Encoding::Windows1252 => {
+                return match WINDOWS1252.iter().position(|&w| w == c) {
+                    Some(i) => Some(0x80 + i as u8),
+                    None => match u8::try_from(u32::from(c)) {
+                        Ok(b) if !(0x80..=0x9F).contains(&b) => Some(b),
+                        _ => None,
+                    },
+                }
+            }
+            Encoding::Cp037 => &CP037,
+            Encoding::Cp500 => &CP500,
+            Encoding::Cp1047 => &CP1047,
+        };
+
+        table.iter().position(|&t| t == c).map(|i| i as u8)
+    }
+}
+
+// Bytes 0x80-0x9F of Windows-1252. The five bytes left undefined by Microsoft decode to the C1
+// control code of the same value, as they do in browsers.
+const WINDOWS1252: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+const CP037: [char; 256] = [
+    '\u{0000}', '\u{0001}', '\u{0002}', '\u{0003}', '\u{009C}', '\u{0009}', '\u{0086}', '\u{007F}',
+    '\u{0097}', '\u{008D}', '\u{008E}', '\u{000B}', '\u{000C}', '\u{000D}', '\u{000E}', '\u{000F}',
+    '\u{0010}', '\u{0011}', '\u{0012}', '\u{0013}', '\u{009D}', '\u{0085}', '\u{0008}', '\u{0087}',
+    '\u{0018}', '\u{0019}', '\u{0092}', '\u{008F}', '\u{001C}', '\u{001D}', '\u{001E}', '\u{001F}',
+    '\u{0080}', '\u{0081}', '\u{0082}', '\u{0083}', '\u{0084}', '\u{000A}', '\u{0017}', '\u{001B}',
+    '\u{0088}', '\u{0089}', '\u{008A}', '\u{008B}', '\u{008C}', '\u{0005}', '\u{0006}', '\u{0007}',
+    '\u{0090}', '\u{0091}', '\u{0016}', '\u{0093}', '\u{0094}', '\u{0095}', '\u{0096}', '\u{0004}',
+    '\u{0098}', '\u{0099}', '\u{009A}', '\u{009B}', '\u{0014}', '\u{0015}', '\u{009E}', '\u{001A}',
+    ' ', '\u{00A0}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E1}', '\u{00E3}', '\u{00E5}',
+    '\u{00E7}', '\u{00F1}', '\u{00A2}', '.', '<', '(', '+', '|', '&', '\u{00E9}', '\u{00EA}',
+    '\u{00EB}', '\u{00E8}', '\u{00ED}', '\u{00EE}', '\u{00EF}', '\u{00EC}', '\u{00DF}', '!', '$',
+    '*', ')', ';', '\u{00AC}', '-', '/', '\u{00C2}', '\u{00C4}', '\u{00C0}', '\u{00C1}',
+    '\u{00C3}', '\u{00C5}', '\u{00C7}', '\u{00D1}', '\u{00A6}', ',', '%', '_', '>', '?',
+    '\u{00F8}', '\u{00C9}', '\u{00CA}', '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}',
+    '\u{00CC}', '`', ':', '#', '@', '\u{0027}', '=', '"', '\u{00D8}', 'a', 'b', 'c', 'd', 'e', 'f',
+    'g', 'h', 'i', '\u{00AB}', '\u{00BB}', '\u{00F0}', '\u{00FD}', '\u{00FE}', '\u{00B1}',
+    '\u{00B0}', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', '\u{00AA}', '\u{00BA}', '\u{00E6}',
+    '\u{00B8}', '\u{00C6}', '\u{00A4}', '\u{00B5}', '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    '\u{00A1}', '\u{00BF}', '\u{00D0}', '\u{00DD}', '\u{00DE}', '\u{00AE}', '^', '\u{00A3}',
+    '\u{00A5}', '\u{00B7}', '\u{00A9}', '\u{00A7}', '\u{00B6}', '\u{00BC}', '\u{00BD}', '\u{00BE}',
+    '[', ']', '\u{00AF}', '\u{00A8}', '\u{00B4}', '\u{00D7}', '{', 'A', 'B', 'C', 'D', 'E', 'F',
+    'G', 'H', 'I', '\u{00AD}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00F3}', '\u{00F5}', '}',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', '\u{00B9}', '\u{00FB}', '\u{00FC}', '\u{00F9}',
+    '\u{00FA}', '\u{00FF}', '\u{005C}', '\u{00F7}', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    '\u{00B2}', '\u{00D4}', '\u{00D6}', '\u{00D2}', '\u{00D3}', '\u{00D5}', '0', '1', '2', '3',
+    '4', '5', '6', '7', '8', '9', '\u{00B3}', '\u{00DB}', '\u{00DC}', '\u{00D9}', '\u{00DA}',
+    '\u{009F}',
+];
+
+// Code page 500 moves seven of the punctuation characters of code page 037.
+const CP500: [char; 256] = patch(
+    CP037,
+    &[
+        (0x4A, '['),
+        (0x4F, '!'),
+        (0x5A, ']'),
+        (0x5F, '^'),
+        (0xB0, '\u{00A2}'),
+        (0xBA, '\u{00AC}'),
+        (0xBB, '|'),
+    ],
+);
+
+// Code page 1047 moves six of the punctuation characters of code page 037.
+const CP1047: [char; 256] = patch(
+    CP037,
+    &[
+        (0x5F, '^'),
+        (0xAD, '['),
+        (0xB0, '\u{00AC}'),
+        (0xBA, '\u{00DD}'),
+        (0xBB, '\u{00A8}'),
+        (0xBD, ']'),
+    ],
+);
+
+const fn patch(mut table: [char; 256], changes: &[(u8, char)]) -> [char; 256] {
+    let mut i = 0;
+    while i < changes.len() {
+        table[changes[i].0 as usize] = changes[i].1;
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ebcdic_round_trip() {
+        for encoding in &[Encoding::Cp037, Encoding::Cp500, Encoding::Cp1047] {
+            let bytes: Vec<u8> = (0..=255).collect();
+            let s = encoding.decode(&bytes).unwrap();
+            assert_eq!(s.chars().count(), 256);
+            assert_eq!(encoding.encode(&s).unwrap().into_owned(), bytes);
+        }
+    }
+
+    #[test]
+    fn ebcdic_characters() {
+        let s = "Hello, World! [0-9] ^ | ¢";
+
+        assert_eq!(
+            Encoding::Cp037.encode(s).unwrap().into_owned(),
+            vec![
+                0xC8, 0x85, 0x93, 0x93, 0x96, 0x6B, 0x40, 0xE6, 0x96, 0x99, 0x93, 0x84, 0x5A, 0x40,
+                0xBA, 0xF0, 0x60, 0xF9, 0xBB, 0x40, 0xB0, 0x40, 0x4F, 0x40, 0x4A
+            ]
+        );
+        assert_eq!(
+            &Encoding::Cp500.encode("[!]^").unwrap()[..],
+            &[0x4A, 0x4F, 0x5A, 0x5F]
+        );
+        assert_eq!(
+            &Encoding::Cp1047.encode("[]^").unwrap()[..],
+            &[0xAD, 0xBD, 0x5F]
+        );
+        assert_eq!(Encoding::Cp037.decode(&[0x25]).unwrap(), "\n");
+    }
+
+    #[test]
+    fn latin1_and_windows1252() {
+        assert_eq!(Encoding::Latin1.decode(b"caf\xE9").unwrap(), "café");
+        assert_eq!(
+            Encoding::Windows1252.decode(b"\x80 caf\xE9").unwrap(),
+            "€ café"
+        );
+        assert_eq!(Encoding::Latin1.decode(b"\x80").unwrap(), "\u{80}");
+
+        assert_eq!(&Encoding::Latin1.encode("café").unwrap()[..], b"caf\xE9");
+        assert_eq!(&Encoding::Windows1252.encode("€").unwrap()[..], b"\x80");
+        assert!(Encoding::Latin1.encode("€").is_err());
+        assert!(Encoding::Windows1252.encode("\u{80}").is_err());
+    }
+
+    #[test]
+    fn ascii_is_borrowed() {
+        assert!(matches!(
+            Encoding::Latin1.decode(b"abc"),
+            Ok(Cow::Borrowed(_))
+        ));
+        assert!(matches!(Encoding::Utf8.encode("abc"), Ok(Cow::Borrowed(_))));
+        assert!(Encoding::Utf8.decode(b"\xFF").is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{de::DeserializeError, ser::SerializeError};
+#[cfg(feature = "schema")]
+use crate::schema::SchemaError;
+use crate::{
+    copybook::CopybookError, de::DeserializeError, layout::LayoutError, ser::SerializeError,
+    structure::StructureError,
+};
 use std::{error::Error as StdError, fmt, io, string};
 
 /// An error produced while parsing fixed width data.
@@ -12,6 +17,18 @@ pub enum Error {
     DeserializeError(DeserializeError),
     /// An error occurred during serialization.
     SerializeError(SerializeError),
+    /// A set of field definitions has an invalid layout.
+    LayoutError(LayoutError),
+    /// A copybook could not be parsed.
+    CopybookError(CopybookError),
+    /// A stream of records does not follow its structure.
+    StructureError(StructureError),
+    /// An error occurred while reading a record. Contains the index of the record, counted from
+    /// `0`, the offset of its first byte in the data, and the error.
+    RecordError(usize, u64, Box<Error>),
+    /// A schema could not be loaded or saved.
+    #[cfg(feature = "schema")]
+    SchemaError(SchemaError),
 }
 
 impl fmt::Display for Error {
@@ -21,6 +38,14 @@ impl fmt::Display for Error {
             Error::FormatError(ref e) => write!(f, "{}", e),
             Error::DeserializeError(ref e) => write!(f, "{}", e),
             Error::SerializeError(ref e) => write!(f, "{}", e),
+            Error::LayoutError(ref e) => write!(f, "{}", e),
+            Error::CopybookError(ref e) => write!(f, "{}", e),
+            Error::StructureError(ref e) => write!(f, "{}", e),
+            Error::RecordError(index, offset, ref e) => {
+                write!(f, "record {} at byte {}: {}", index, offset, e)
+            }
+            #[cfg(feature = "schema")]
+            Error::SchemaError(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -43,6 +68,31 @@ impl From<SerializeError> for Error {
     }
 }
 
+impl From<LayoutError> for Error {
+    fn from(e: LayoutError) -> Self {
+        Error::LayoutError(e)
+    }
+}
+
+impl From<CopybookError> for Error {
+    fn from(e: CopybookError) -> Self {
+        Error::CopybookError(e)
+    }
+}
+
+impl From<StructureError> for Error {
+    fn from(e: StructureError) -> Self {
+        Error::StructureError(e)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl From<SchemaError> for Error {
+    fn from(e: SchemaError) -> Self {
+        Error::SchemaError(e)
+    }
+}
+
 impl StdError for Error {
     fn cause(&self) -> Option<&dyn StdError> {
         match self {
@@ -50,6 +100,12 @@ impl StdError for Error {
             Error::FormatError(ref e) => Some(e),
             Error::DeserializeError(ref e) => Some(e),
             Error::SerializeError(ref e) => Some(e),
+            Error::LayoutError(ref e) => Some(e),
+            Error::CopybookError(ref e) => Some(e),
+            Error::StructureError(ref e) => Some(e),
+            Error::RecordError(_, _, ref e) => Some(e.as_ref()),
+            #[cfg(feature = "schema")]
+            Error::SchemaError(ref e) => Some(e),
         }
     }
 }
@@ -0,0 +1,495 @@
+use crate::{FieldConfig, FieldSet};
+use std::{error::Error as StdError, fmt, ops::Range};
+
+/// A problem found while validating the layout of a `FieldSet`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutIssue {
+    /// The end of the field's range is before its start.
+    Inverted {
+        /// Name of the field, if it has one.
+        name: Option<String>,
+        /// Byte range of the field.
+        range: Range<usize>,
+    },
+    /// The field does not cover any bytes.
+    ZeroWidth {
+        /// Name of the field, if it has one.
+        name: Option<String>,
+        /// Byte range of the field.
+        range: Range<usize>,
+    },
+    /// Two fields share at least one byte of the record.
+    Overlap {
+        /// Name of the first field, if it has one.
+        first_name: Option<String>,
+        /// Byte range of the first field.
+        first: Range<usize>,
+        /// Name of the second field, if it has one.
+        second_name: Option<String>,
+        /// Byte range of the second field.
+        second: Range<usize>,
+    },
+    /// A field starts before the field defined ahead of it. Fields are written in the order they
+    /// are defined, so such a layout cannot be serialized correctly.
+    OutOfOrder {
+        /// Name of the field, if it has one.
+        name: Option<String>,
+        /// Byte range of the field.
+        range: Range<usize>,
+        /// Byte range of the field defined ahead of it.
+        previous: Range<usize>,
+    },
+    /// A range of bytes in the record that is not covered by any field.
+    Gap(Range<usize>),
+    /// More than one field has the same name.
+    DuplicateName {
+        /// The duplicated name.
+        name: String,
+        /// Byte ranges of every field with this name.
+        ranges: Vec<Range<usize>>,
+    },
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutIssue::Inverted { name, range } => write!(
+                f,
+                "field {} has an inverted range",
+                label(name.as_deref(), range)
+            ),
+            LayoutIssue::ZeroWidth { name, range } => write!(
+                f,
+                "field {} has a width of zero",
+                label(name.as_deref(), range)
+            ),
+            LayoutIssue::Overlap {
+                first_name,
+                first,
+                second_name,
+                second,
+            } => write!(
+                f,
+                "field {} overlaps field {}",
+                label(first_name.as_deref(), first),
+                label(second_name.as_deref(), second)
+            ),
+            LayoutIssue::OutOfOrder {
+                name,
+                range,
+                previous,
+            } => write!(
+                f,
+                "field {} starts before the previous field at {}..{}",
+                label(name.as_deref(), range),
+                previous.start,
+                previous.end
+            ),
+            LayoutIssue::Gap(range) => {
+                write!(
+                    f,
+                    "bytes {}..{} are not covered by any field",
+                    range.start, range.end
+                )
+            }
+            LayoutIssue::DuplicateName { name, ranges } => {
+                let ranges: Vec<String> = ranges
+                    .iter()
+                    .map(|r| format!("{}..{}", r.start, r.end))
+                    .collect();
+                write!(f, "name `{}` is used by fields {}", name, ranges.join(", "))
+            }
+        }
+    }
+}
+
+/// The result of validating the layout of a `FieldSet`.
+///
+/// Issues are split into errors, which will garble records when reading or writing, and warnings,
+/// which are legal but often point to a typo in the field definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutReport {
+    width: usize,
+    errors: Vec<LayoutIssue>,
+    warnings: Vec<LayoutIssue>,
+}
+
+impl LayoutReport {
+    /// The total width in bytes of a record described by the layout.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Issues that make the layout invalid.
+    pub fn errors(&self) -> &[LayoutIssue] {
+        &self.errors
+    }
+
+    /// Issues that are worth a look, but do not make the layout invalid.
+    pub fn warnings(&self) -> &[LayoutIssue] {
+        &self.warnings
+    }
+
+    /// Returns `true` if no errors were found.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the report into a `Result`, failing with `LayoutError` if any errors were found.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..4),
+    ///     FieldSet::new_field(3..6),
+    /// ]);
+    ///
+    /// assert!(fields.validate().into_result().is_err());
+    /// ```
+    pub fn into_result(self) -> Result<Self, LayoutError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(LayoutError(self))
+        }
+    }
+}
+
+/// Error returned when a `FieldSet` layout contains errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutError(LayoutReport);
+
+impl LayoutError {
+    /// The report describing every issue found in the layout.
+    pub fn report(&self) -> &LayoutReport {
+        &self.0
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let errors: Vec<String> = self.0.errors.iter().map(|e| e.to_string()).collect();
+        write!(f, "invalid layout: {}", errors.join("; "))
+    }
+}
+
+impl StdError for LayoutError {
+    fn cause(&self) -> Option<&dyn StdError> {
+        None
+    }
+}
+
+fn label(name: Option<&str>, range: &Range<usize>) -> String {
+    match name {
+        Some(name) => format!("`{}` ({}..{})", name, range.start, range.end),
+        None => format!("{}..{}", range.start, range.end),
+    }
+}
+
+pub(crate) fn validate(fields: &FieldSet, strict: bool) -> LayoutReport {
+    let mut report = LayoutReport {
+        width: 0,
+        errors: vec![],
+        warnings: vec![],
+    };
+
+    // A tagged enum has one layout per variant, the record is as wide as the widest of them.
+    for fields in layouts(fields.clone().flatten(), 0) {
+        let layout = validate_fields(&fields, strict);
+        report.width = report.width.max(layout.width);
+
+        for issue in layout.errors {
+            if !report.errors.contains(&issue) {
+                report.errors.push(issue);
+            }
+        }
+        for issue in layout.warnings {
+            if !report.warnings.contains(&issue) {
+                report.warnings.push(issue);
+            }
+        }
+    }
+
+    report
+}
+
+// Expands the fields of each variant of the tagged fields from `from` on, after their tag.
+fn layouts(fields: Vec<FieldConfig>, from: usize) -> Vec<Vec<FieldConfig>> {
+    let tag = match fields[from..].iter().position(|f| !f.variants.is_empty()) {
+        Some(pos) => from + pos,
+        None => return vec![fields],
+    };
+
+    let mut expanded_layouts = vec![];
+    for variant in &fields[tag].variants {
+        let variant_fields = variant.fields.clone().flatten();
+        let next = tag + 1 + variant_fields.len();
+
+        let mut expanded = fields[..=tag].to_vec();
+        expanded.extend(variant_fields);
+        expanded.extend_from_slice(&fields[tag + 1..]);
+        expanded_layouts.extend(layouts(expanded, next));
+    }
+
+    expanded_layouts
+}
+
+fn validate_fields(fields: &[FieldConfig], strict: bool) -> LayoutReport {
+    let mut errors = vec![];
+    let mut warnings = vec![];
+
+    let mut spans: Vec<&FieldConfig> = vec![];
+    let mut previous: Option<&FieldConfig> = None;
+
+    for field in fields {
+        let range = field.range.clone();
+
+        if range.end < range.start {
+            errors.push(LayoutIssue::Inverted {
+                name: field.name.clone(),
+                range,
+            });
+            continue;
+        }
+
+        if range.start == range.end {
+            warnings.push(LayoutIssue::ZeroWidth {
+                name: field.name.clone(),
+                range,
+            });
+            continue;
+        }
+
+        if let Some(prev) = previous {
+            if range.start < prev.range.start {
+                errors.push(LayoutIssue::OutOfOrder {
+                    name: field.name.clone(),
+                    range: range.clone(),
+                    previous: prev.range.clone(),
+                });
+            }
+        }
+
+        previous = Some(field);
+        spans.push(field);
+    }
+
+    for (i, first) in spans.iter().enumerate() {
+        for second in &spans[i + 1..] {
+            if first.range.start < second.range.end && second.range.start < first.range.end {
+                errors.push(LayoutIssue::Overlap {
+                    first_name: first.name.clone(),
+                    first: first.range.clone(),
+                    second_name: second.name.clone(),
+                    second: second.range.clone(),
+                });
+            }
+        }
+    }
+
+    let mut sorted: Vec<Range<usize>> = spans.iter().map(|f| f.range.clone()).collect();
+    sorted.sort_by_key(|r| r.start);
+
+    let mut covered = 0;
+    for range in &sorted {
+        if range.start > covered {
+            let gap = LayoutIssue::Gap(covered..range.start);
+            if strict {
+                errors.push(gap);
+            } else {
+                warnings.push(gap);
+            }
+        }
+        covered = covered.max(range.end);
+    }
+
+    let mut names: Vec<(&str, Vec<Range<usize>>)> = vec![];
+    for field in fields {
+        if let Some(name) = field.name.as_deref() {
+            match names.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, ranges)) => ranges.push(field.range.clone()),
+                None => names.push((name, vec![field.range.clone()])),
+            }
+        }
+    }
+
+    for (name, ranges) in names {
+        if ranges.len() > 1 {
+            errors.push(LayoutIssue::DuplicateName {
+                name: name.to_string(),
+                ranges,
+            });
+        }
+    }
+
+    LayoutReport {
+        width: covered,
+        errors,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn valid_layout() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).name("a"),
+            FieldSet::Seq(vec![
+                FieldSet::new_field(4..6).name("b"),
+                FieldSet::new_field(6..10).name("c"),
+            ]),
+        ]);
+
+        let report = fields.validate();
+
+        assert!(report.is_valid());
+        assert!(report.warnings().is_empty());
+        assert_eq!(report.width(), 10);
+    }
+
+    #[test]
+    fn overlapping_fields() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).name("a"),
+            FieldSet::new_field(3..6).name("b"),
+        ]);
+
+        let report = fields.validate();
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::Overlap {
+                first_name: Some("a".to_string()),
+                first: 0..4,
+                second_name: Some("b".to_string()),
+                second: 3..6,
+            }]
+        );
+    }
+
+    #[test]
+    fn gaps_are_warnings_unless_strict() {
+        let fields = FieldSet::Seq(vec![FieldSet::new_field(2..4), FieldSet::new_field(6..8)]);
+
+        let report = fields.validate();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings(),
+            &[LayoutIssue::Gap(0..2), LayoutIssue::Gap(4..6)]
+        );
+        assert_eq!(report.width(), 8);
+
+        let report = fields.validate_strict();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::Gap(0..2), LayoutIssue::Gap(4..6)]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn inverted_and_zero_width_fields() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2),
+            FieldSet::new_field(4..2).name("inverted"),
+            FieldSet::new_field(2..2),
+            FieldSet::new_field(2..4),
+        ]);
+
+        let report = fields.validate();
+
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::Inverted {
+                name: Some("inverted".to_string()),
+                range: 4..2,
+            }]
+        );
+        assert_eq!(
+            report.warnings(),
+            &[LayoutIssue::ZeroWidth {
+                name: None,
+                range: 2..2,
+            }]
+        );
+    }
+
+    #[test]
+    fn out_of_order_fields() {
+        let fields = FieldSet::Seq(vec![FieldSet::new_field(4..8), FieldSet::new_field(0..4)]);
+
+        let report = fields.validate();
+
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::OutOfOrder {
+                name: None,
+                range: 0..4,
+                previous: 4..8,
+            }]
+        );
+    }
+
+    #[test]
+    fn variant_layouts() {
+        let fields = FieldSet::new_field(0..1)
+            .variant(
+                "H",
+                "Header",
+                FieldSet::Seq(vec![FieldSet::new_field(1..9)]),
+            )
+            .variant(
+                "D",
+                "Detail",
+                FieldSet::Seq(vec![
+                    FieldSet::new_field(1..5).name("name"),
+                    FieldSet::new_field(4..12).name("amount"),
+                ]),
+            )
+            .variant("T", "Trailer", FieldSet::Seq(vec![]));
+
+        let report = fields.validate();
+
+        assert_eq!(report.width(), 12);
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::Overlap {
+                first_name: Some("name".to_string()),
+                first: 1..5,
+                second_name: Some("amount".to_string()),
+                second: 4..12,
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2).name("a"),
+            FieldSet::new_field(2..4).name("b"),
+            FieldSet::new_field(4..6).name("a"),
+        ]);
+
+        let report = fields.validate();
+
+        assert_eq!(
+            report.errors(),
+            &[LayoutIssue::DuplicateName {
+                name: "a".to_string(),
+                ranges: vec![0..2, 4..6],
+            }]
+        );
+        assert_eq!(
+            report.into_result().unwrap_err().to_string(),
+            "invalid layout: name `a` is used by fields 0..2, 4..6"
+        );
+    }
+}
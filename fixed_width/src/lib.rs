@@ -16,9 +16,21 @@ not self describing, you will need to define the set of
 [`FieldSet`](enum.FieldSet.html)
 definitions for your data up front so the (de)serialization code can work.
 
+With the `schema` feature enabled, field definitions can also be loaded from and saved to JSON,
+TOML or YAML files, see the [`schema`](schema/index.html) module. Layouts documented as COBOL
+copybooks can be imported with the [`copybook`](copybook/index.html) module. Files that mix
+several record types, such as a header, details and a trailer, can be read with
+[`RecordTypes`](struct.RecordTypes.html), or into an enum selected by a tag field, see
+[`FieldSet::variant`](enum.FieldSet.html#method.variant). The nesting of their headers and trailers,
+and the counts and totals carried by the trailers, can be checked with
+[`Structure`](struct.Structure.html). Such files can be written with
+[`TotalsWriter`](struct.TotalsWriter.html), which fills in the trailers as the details are written.
+
 Several errors may occur while using the library. These are defined in the
 [`Error`](enum.Error.html)
-type.
+type. To find every invalid field of a record or of a file rather than stopping at the first one,
+see [`Deserializer::lenient`](struct.Deserializer.html#method.lenient) and
+[`Reader::validate`](struct.Reader.html#method.validate).
 
 # Installing
 
@@ -84,9 +96,8 @@ impl FixedWidth for Person {
 
 let mut reader = Reader::from_string("foobar 25barfoo 35").width(9);
 let records: Vec<Person> = reader
-    .byte_reader()
+    .deserialize()
     .filter_map(result::Result::ok)
-    .map(|bytes| fixed_width::from_bytes(&bytes).unwrap())
     .collect();
 ```
 !*/
@@ -94,22 +105,48 @@ let records: Vec<Person> = reader
 #![deny(missing_docs)]
 
 pub use crate::de::{
-    deserialize, from_bytes, from_bytes_with_fields, from_str, from_str_with_fields,
-    DeserializeError, Deserializer,
+    deserialize, from_bytes, from_bytes_lenient, from_bytes_with_fields, from_str,
+    from_str_with_fields, DeserializeError, Deserializer,
 };
 pub use crate::{
+    booleans::Booleans,
+    builder::FieldSetBuilder,
+    encoding::Encoding,
     error::Error,
-    reader::{ByteReader, Reader, StringReader},
+    layout::{LayoutError, LayoutIssue, LayoutReport},
+    null::Null,
+    numeric::{Codec, Endian, Sign},
+    reader::{
+        ByteReader, DeserializeRecords, Reader, RecordErrors, Records, StringReader,
+        ValidationReport,
+    },
+    records::RecordTypes,
     ser::{to_bytes, to_string, to_writer, to_writer_with_fields, SerializeError, Serializer},
-    writer::{AsByteSlice, Writer},
+    structure::{Group, Structure, StructureError, StructureValidator},
+    writer::{AsByteSlice, Totals, TotalsWriter, Writer},
 };
 use std::{ops::Range, result};
 
+mod booleans;
+mod builder;
+pub mod copybook;
+#[cfg(feature = "chrono")]
+mod date;
 mod de;
+#[cfg(feature = "rust_decimal")]
+pub mod decimal;
+mod encoding;
 mod error;
+mod layout;
 mod macros;
+mod null;
+mod numeric;
 mod reader;
+mod records;
+#[cfg(feature = "schema")]
+pub mod schema;
 mod ser;
+mod structure;
 mod writer;
 
 /// Convenience type for `Result` types pertaining to this library.
@@ -123,6 +160,11 @@ pub trait FixedWidth {
 
 /// Justification of a fixed width field.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Justify {
     /// Justify the field to the left in the record.
     Left,
@@ -140,6 +182,70 @@ impl<T: AsRef<str>> From<T> for Justify {
     }
 }
 
+/// How the padding of a field is removed when it is read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Trim {
+    /// Strip the pad character from the padded side of the field only: the end of a left
+    /// justified field and the start of a right justified one. This undoes the padding added when
+    /// the field is written, so spaces inside the value are kept.
+    Justified,
+    /// Strip the pad character from both ends of the field.
+    Pad,
+    /// Strip whitespace from both ends of the field, whatever the pad character.
+    Whitespace,
+    /// Keep the field as it is.
+    Raw,
+}
+
+impl<T: AsRef<str>> From<T> for Trim {
+    fn from(s: T) -> Self {
+        match s.as_ref().to_lowercase().trim() {
+            "justified" => Trim::Justified,
+            "pad" => Trim::Pad,
+            "whitespace" => Trim::Whitespace,
+            "raw" => Trim::Raw,
+            _ => panic!("Trim must be 'justified', 'pad', 'whitespace' or 'raw'"),
+        }
+    }
+}
+
+/// What to do with a value that is longer than its field when it is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Overflow {
+    /// Fail with `SerializeError::FieldOverflow`, naming the field and the value.
+    Error,
+    /// Cut the end of the value off, keeping its start.
+    TruncateRight,
+    /// Cut the start of the value off, keeping its end, such as the low order digits of a number.
+    TruncateLeft,
+    /// Cut the end of the value off and mark it with `...`.
+    Ellipsis,
+}
+
+impl<T: AsRef<str>> From<T> for Overflow {
+    fn from(s: T) -> Self {
+        match s.as_ref().to_lowercase().trim() {
+            "error" => Overflow::Error,
+            "truncate_right" => Overflow::TruncateRight,
+            "truncate_left" => Overflow::TruncateLeft,
+            "ellipsis" => Overflow::Ellipsis,
+            _ => {
+                panic!("Overflow must be 'error', 'truncate_right', 'truncate_left' or 'ellipsis'")
+            }
+        }
+    }
+}
+
 /// Defines a field in a fixed width record. There can be 1 or more fields in a fixed width record.
 #[derive(Debug, Clone)]
 pub struct FieldConfig {
@@ -151,6 +257,33 @@ pub struct FieldConfig {
     pad_with: char,
     /// The justification (Left or Right) of the field.
     justify: Justify,
+    /// How the padding of the field is removed when it is read.
+    trim: Trim,
+    /// What to do with a value that is longer than the field, if not the policy of the serializer.
+    overflow: Option<Overflow>,
+    /// How the value of the field is represented.
+    codec: Codec,
+    /// The number of digits after the implied decimal point.
+    scale: usize,
+    /// Where the sign of a signed number is written.
+    sign: Sign,
+    /// The date format of the field.
+    #[cfg(feature = "chrono")]
+    format: Option<String>,
+    /// How the values of a boolean field are written and read.
+    booleans: Option<Booleans>,
+    /// How a missing value is represented in the field.
+    null: Option<Null>,
+    /// The layouts of the variants of an enum, selected by the value of this field.
+    variants: Vec<Variant>,
+}
+
+/// The layout of a variant of an enum, and the value of the tag field that selects it.
+#[derive(Debug, Clone)]
+pub(crate) struct Variant {
+    pub(crate) tag: String,
+    pub(crate) name: String,
+    pub(crate) fields: FieldSet,
 }
 
 impl Default for FieldConfig {
@@ -160,6 +293,16 @@ impl Default for FieldConfig {
             range: 0..0,
             pad_with: ' ',
             justify: Justify::Left,
+            trim: Trim::Justified,
+            overflow: None,
+            codec: Codec::Text,
+            scale: 0,
+            sign: Sign::Inline,
+            #[cfg(feature = "chrono")]
+            format: None,
+            booleans: None,
+            null: None,
+            variants: vec![],
         }
     }
 }
@@ -182,6 +325,14 @@ impl FieldConfig {
     fn width(&self) -> usize {
         self.range.end - self.range.start
     }
+
+    // The name of the field for error messages, or its range if it has no name.
+    fn label(&self) -> String {
+        match self.name {
+            Some(ref name) => name.clone(),
+            None => format!("{}..{}", self.range.start, self.range.end),
+        }
+    }
 }
 
 /// Field structure definition.
@@ -208,6 +359,21 @@ impl FieldSet {
         })
     }
 
+    /// Creates a builder that assigns consecutive byte ranges to fields from their widths.
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::builder()
+    ///     .field("name", 6)
+    ///     .field("age", 3)
+    ///     .filler(2)
+    ///     .build();
+    /// ```
+    pub fn builder() -> FieldSetBuilder {
+        FieldSetBuilder::new()
+    }
+
     /// Sets the name of this field. Mainly used when deserializing into a HashMap to derive the keys.
     /// (This method is not valid on `FieldSet::Seq` and cause panic)
     ///
@@ -231,8 +397,71 @@ impl FieldSet {
         }
     }
 
+    /// Makes this field the tag of an enum: a record whose tag holds `tag` is read as the variant
+    /// `name`, with the given fields. The fields of the variant follow the tag when it is written,
+    /// and `tag` is written in place of the name of the variant. Struct, tuple, newtype and unit
+    /// variants are supported.
+    ///
+    /// Reading a record whose tag matches no variant fails with
+    /// `DeserializeError::UnknownRecordType`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, FixedWidth};
+    /// use serde_derive::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// enum FileRecord {
+    ///     Header { date: String },
+    ///     Detail { name: String, amount: u32 },
+    ///     Trailer,
+    /// }
+    ///
+    /// impl FixedWidth for FileRecord {
+    ///     fn fields() -> FieldSet {
+    ///         FieldSet::new_field(0..1)
+    ///             .variant("H", "Header", FieldSet::Seq(vec![FieldSet::new_field(1..9)]))
+    ///             .variant(
+    ///                 "D",
+    ///                 "Detail",
+    ///                 FieldSet::Seq(vec![FieldSet::new_field(1..5), FieldSet::new_field(5..9)]),
+    ///             )
+    ///             .variant("T", "Trailer", FieldSet::Seq(vec![]))
+    ///     }
+    /// }
+    ///
+    /// let record: FileRecord = fixed_width::from_str("DAnn 0012").unwrap();
+    /// assert_eq!(record, FileRecord::Detail { name: "Ann".to_string(), amount: 12 });
+    ///
+    /// let header = FileRecord::Header { date: "20240131".to_string() };
+    /// assert_eq!(fixed_width::to_string(&header).unwrap(), "H20240131");
+    /// ```
+    pub fn variant<T: Into<String>, N: Into<String>>(
+        mut self,
+        tag: T,
+        name: N,
+        fields: FieldSet,
+    ) -> Self {
+        match &mut self {
+            Self::Item(conf) => {
+                conf.variants.push(Variant {
+                    tag: tag.into(),
+                    name: name.into(),
+                    fields,
+                });
+                self
+            }
+            _ => panic!("Setting a variant on FieldSet::Seq is not feasible."),
+        }
+    }
+
     /// Sets the character to use as padding the value of this field to its byte width.
     ///
+    /// The pad character is written in the encoding of the record, so it can take more than one
+    /// byte, such as `'·'` in UTF-8. Writing fails if it does not evenly fill the bytes left in the
+    /// field. The same padding is stripped when the field is read.
+    ///
     /// ### Example
     ///
     /// ```rust
@@ -281,6 +510,248 @@ impl FieldSet {
         }
     }
 
+    /// Sets how the padding of this field is removed when it is read. Defaults to
+    /// `Trim::Justified`, which strips the pad character from the padded side only, so that
+    /// leading spaces of a left justified field are kept.
+    ///
+    /// A field made only of a digit pad character, such as `0000`, is read as a single one of it,
+    /// so that it still reads as a number. Numbers are read ignoring surrounding whitespace.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Justify, Trim};
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..6),
+    ///     FieldSet::new_field(6..10).pad_with('0'),
+    ///     FieldSet::new_field(10..14).trim(Trim::Raw),
+    ///     FieldSet::new_field(14..18).justify(Justify::Right).trim(Trim::Whitespace),
+    /// ]);
+    ///
+    /// let values: (String, u32, String, String) =
+    ///     fixed_width::from_str_with_fields("  abc 1200ab    cd", fields).unwrap();
+    /// assert_eq!(values, ("  abc".to_string(), 12, "ab  ".to_string(), "cd".to_string()));
+    /// ```
+    pub fn trim<T: Into<Trim>>(mut self, val: T) -> Self {
+        let val = val.into();
+        match self {
+            Self::Item(ref mut config) => {
+                config.trim = val;
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.trim(val)).collect()),
+        }
+    }
+
+    /// Sets what to do with a value that is longer than this field when it is written, instead of
+    /// the policy of the `Serializer`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Justify, Overflow, Writer};
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..6).overflow(Overflow::Ellipsis),
+    ///     FieldSet::new_field(6..9).justify(Justify::Right).overflow(Overflow::TruncateLeft),
+    /// ]);
+    ///
+    /// let mut wrtr = Writer::from_memory();
+    /// fixed_width::to_writer_with_fields(&mut wrtr, &("Rosalind", 12345), fields).unwrap();
+    ///
+    /// let s: String = wrtr.into();
+    /// assert_eq!(s, "Ros...345");
+    /// ```
+    pub fn overflow<T: Into<Overflow>>(mut self, val: T) -> Self {
+        let val = val.into();
+        match self {
+            Self::Item(ref mut config) => {
+                config.overflow = Some(val);
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.overflow(val)).collect()),
+        }
+    }
+
+    /// Sets how the value of this field is represented, such as packed decimal or a binary
+    /// integer. Defaults to `Codec::Text`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Codec, Endian, FieldSet};
+    ///
+    /// let field = FieldSet::new_field(0..4).codec(Codec::Packed { signed: true });
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..2),
+    ///     FieldSet::new_field(2..4),
+    /// ])
+    /// .codec(Codec::Binary { endian: Endian::Little, signed: false });
+    /// ```
+    pub fn codec(mut self, val: Codec) -> Self {
+        match self {
+            Self::Item(ref mut config) => {
+                config.codec = val;
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.codec(val)).collect()),
+        }
+    }
+
+    /// Sets the number of digits after the implied decimal point of a numeric field. Defaults
+    /// to `0`.
+    ///
+    /// With a scale of `2`, the digits `0001234567` are read as `12345.67`, and `12345.67` is
+    /// written as `1234567`. Values are shifted exactly: reading a fraction into an integer, or
+    /// writing a value with more decimals than the scale, fails instead of rounding.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::new_field(0..10).scale(2);
+    /// let amount: f64 = fixed_width::from_str_with_fields("0001234567", fields.clone()).unwrap();
+    /// let text: String = fixed_width::from_str_with_fields("0001234567", fields).unwrap();
+    ///
+    /// assert_eq!(amount, 12345.67);
+    /// assert_eq!(text, "12345.67");
+    /// ```
+    pub fn scale(mut self, val: usize) -> Self {
+        match self {
+            Self::Item(ref mut config) => {
+                config.scale = val;
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.scale(val)).collect()),
+        }
+    }
+
+    /// Sets where the sign of a signed number is written in a text field. Defaults to
+    /// `Sign::Inline`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Justify, Sign, Writer};
+    ///
+    /// let fields = FieldSet::new_field(0..5)
+    ///     .justify(Justify::Right)
+    ///     .pad_with('0')
+    ///     .sign(Sign::Leading);
+    ///
+    /// let mut wrtr = Writer::from_memory();
+    /// fixed_width::to_writer_with_fields(&mut wrtr, &-12, fields).unwrap();
+    ///
+    /// let s: String = wrtr.into();
+    /// assert_eq!(s, "-0012");
+    /// ```
+    pub fn sign<T: Into<Sign>>(mut self, val: T) -> Self {
+        let val = val.into();
+        match self {
+            Self::Item(ref mut config) => {
+                config.sign = val;
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.sign(val)).collect()),
+        }
+    }
+
+    /// Sets the date format of the field. Dates and times are read as ISO 8601 text, such as
+    /// `2024-01-31`, `12:34:56` or `2024-01-31T12:34:56`, so they can be deserialized into
+    /// `chrono::NaiveDate`, `NaiveTime` and `NaiveDateTime`, and are written back in the format
+    /// of the field. Blank and all zero fields are read as `None` into an `Option`.
+    ///
+    /// Only available with the `chrono` feature enabled. The format is made of the following
+    /// specifiers and literal characters:
+    ///
+    /// - `%Y`: four digit year.
+    /// - `%y`: two digit year, `00` to `69` for 2000 to 2069 and `70` to `99` for 1970 to 1999.
+    /// - `%C`: one digit century before a `%y`, `0` for 19xx and `1` for 20xx, as in `CYYMMDD`.
+    /// - `%m`: two digit month.
+    /// - `%d`: two digit day of the month.
+    /// - `%j`: three digit day of the year, for Julian dates such as `YYDDD`.
+    /// - `%H`, `%M` and `%S`: two digit hour, minute and second.
+    /// - `%%`: a literal `%`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use chrono::NaiveDate;
+    /// use fixed_width::FieldSet;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..8).format("%Y%m%d"),
+    ///     FieldSet::new_field(8..13).format("%y%j"),
+    /// ]);
+    ///
+    /// let (opened, closed): (NaiveDate, Option<NaiveDate>) =
+    ///     fixed_width::from_str_with_fields("2024013100000", fields).unwrap();
+    ///
+    /// assert_eq!(opened, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+    /// assert_eq!(closed, None);
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn format<T: Into<String>>(mut self, val: T) -> Self {
+        let val = val.into();
+        match self {
+            Self::Item(ref mut config) => {
+                config.format = Some(val);
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.format(val.clone())).collect()),
+        }
+    }
+
+    /// Sets how the values of a boolean field are written and read, such as `Y` and `N`. Without
+    /// a mapping, `1` and `0` are written and any value other than blank or `0` is read as `true`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Booleans, FieldSet};
+    ///
+    /// let fields = FieldSet::new_field(0..1).booleans(Booleans::yes_no());
+    /// let b: bool = fixed_width::from_str_with_fields("N", fields).unwrap();
+    ///
+    /// assert!(!b);
+    /// ```
+    pub fn booleans(mut self, val: Booleans) -> Self {
+        match self {
+            Self::Item(ref mut config) => {
+                config.booleans = Some(val);
+                self
+            }
+            Self::Seq(seq) => {
+                Self::Seq(seq.into_iter().map(|fs| fs.booleans(val.clone())).collect())
+            }
+        }
+    }
+
+    /// Sets how a missing value is represented in the field, such as all zeros or `NULL`, so that
+    /// it is read and written as `None`. A blank field is always read as `None`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Null};
+    ///
+    /// let fields = FieldSet::new_field(0..8).null(Null::Fill('9'));
+    /// let n: Option<u32> = fixed_width::from_str_with_fields("99999999", fields).unwrap();
+    ///
+    /// assert_eq!(n, None);
+    /// ```
+    pub fn null(mut self, val: Null) -> Self {
+        match self {
+            Self::Item(ref mut config) => {
+                config.null = Some(val);
+                self
+            }
+            Self::Seq(seq) => Self::Seq(seq.into_iter().map(|fs| fs.null(val.clone())).collect()),
+        }
+    }
+
     /// Append `FieldSet` with the given item.
     ///
     /// ### Example
@@ -428,6 +899,60 @@ impl FieldSet {
 
         flatten
     }
+
+    /// Checks the layout of the fields for overlapping, inverted and zero-width ranges, fields
+    /// defined out of order, duplicate names and bytes that are not covered by any field.
+    /// Uncovered bytes are reported as warnings, see `validate_strict` to treat them as errors.
+    /// The fields of each variant set with `variant` are checked as if they followed the tag, and
+    /// the width of the record is that of its widest variant.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, LayoutIssue};
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..6).name("name"),
+    ///     FieldSet::new_field(6..9).name("age"),
+    ///     FieldSet::new_field(11..15).name("height"),
+    /// ]);
+    ///
+    /// let report = fields.validate();
+    ///
+    /// assert!(report.is_valid());
+    /// assert_eq!(report.width(), 15);
+    /// assert_eq!(report.warnings(), &[LayoutIssue::Gap(9..11)]);
+    /// ```
+    pub fn validate(&self) -> LayoutReport {
+        layout::validate(self, false)
+    }
+
+    /// Same as `validate`, but bytes that are not covered by any field are reported as errors.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, LayoutIssue};
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..6).name("name"),
+    ///     FieldSet::new_field(7..9).name("age"),
+    /// ]);
+    ///
+    /// let report = fields.validate_strict();
+    ///
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.errors(), &[LayoutIssue::Gap(6..7)]);
+    /// ```
+    pub fn validate_strict(&self) -> LayoutReport {
+        layout::validate(self, true)
+    }
+}
+
+impl From<Range<usize>> for FieldSet {
+    fn from(range: Range<usize>) -> Self {
+        FieldSet::new_field(range)
+    }
 }
 
 impl IntoIterator for FieldSet {
@@ -444,6 +969,11 @@ impl IntoIterator for FieldSet {
 
 /// The type of line break between each record that should be inserted or skipped while reading.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum LineBreak {
     /// No linebreak
     None,
@@ -478,6 +1008,21 @@ impl LineBreak {
     }
 }
 
+/// What a `Reader` does with bytes at the end of the data that are too few to make a whole
+/// record, such as the end of a truncated file.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Trailing {
+    /// Fail with `DeserializeError::PartialRecord`.
+    #[default]
+    Error,
+    /// Read the bytes as a record shorter than the record width.
+    Short,
+    /// Read the bytes as a record padded to the record width with the given byte.
+    Pad(u8),
+    /// Skip the bytes.
+    Ignore,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
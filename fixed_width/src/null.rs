@@ -0,0 +1,62 @@
+use crate::Encoding;
+
+/// How a missing value is represented in a field, so that it is read and written as `None`.
+///
+/// A blank field is always read as `None`. With a null representation, fields holding it are read
+/// as `None` as well, before the value of the field is decoded, and `None` is written as the
+/// null representation instead of padding.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{FieldSet, Null, Writer};
+///
+/// let fields = FieldSet::Seq(vec![
+///     FieldSet::new_field(0..4).null(Null::Fill('9')),
+///     FieldSet::new_field(4..8).null(Null::Text("NULL".to_string())),
+///     FieldSet::new_field(8..10).null(Null::Byte(0x00)),
+/// ]);
+///
+/// let values: (Option<u32>, Option<String>, Option<u16>) =
+///     fixed_width::from_bytes_with_fields(b"9999NULL\x00\x00", fields.clone()).unwrap();
+/// assert_eq!(values, (None, None, None));
+///
+/// let mut wrtr = Writer::from_memory();
+/// fixed_width::to_writer_with_fields(&mut wrtr, &values, fields).unwrap();
+///
+/// let bytes: Vec<u8> = wrtr.into();
+/// assert_eq!(bytes, b"9999NULL\x00\x00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Null {
+    /// The field holds the given text, such as `NULL`, once trimmed. It is written padded like
+    /// any other text, and writing fails with `SerializeError::FieldOverflow` if it is longer than
+    /// the field.
+    Text(String),
+    /// Every character of the field is the given character, such as all zeros or all nines.
+    Fill(char),
+    /// Every byte of the field is the given byte, such as the low values (`0x00`) or high values
+    /// (`0xFF`) of mainframe data. The bytes are compared before decoding.
+    Byte(u8),
+}
+
+impl Null {
+    pub(crate) fn matches(&self, bytes: &[u8], encoding: Encoding) -> bool {
+        match *self {
+            Null::Byte(b) => !bytes.is_empty() && bytes.iter().all(|x| *x == b),
+            Null::Text(ref text) => match encoding.decode(bytes) {
+                Ok(s) => s.trim() == text.trim(),
+                Err(_) => false,
+            },
+            Null::Fill(c) => match encoding.decode(bytes) {
+                Ok(s) => !s.is_empty() && s.chars().all(|x| x == c),
+                Err(_) => false,
+            },
+        }
+    }
+}
@@ -0,0 +1,881 @@
+use crate::{de::DeserializeError, ser::SerializeError, Encoding};
+
+/// How the value of a field is represented in the record.
+///
+/// Numeric codecs are read and written through the decimal text of the value, so a packed or
+/// binary field can be deserialized into any integer or float type, or into a `String`.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{Codec, Endian, FieldSet};
+/// use std::collections::HashMap;
+///
+/// let fields = FieldSet::Seq(vec![
+///     FieldSet::new_field(0..3).name("packed").codec(Codec::Packed { signed: true }),
+///     FieldSet::new_field(3..5).name("binary").codec(Codec::Binary {
+///         endian: Endian::Big,
+///         signed: false,
+///     }),
+/// ]);
+///
+/// let bytes = [0x12, 0x34, 0x5D, 0x01, 0x00];
+/// let h: HashMap<String, i64> = fixed_width::from_bytes_with_fields(&bytes, fields).unwrap();
+///
+/// assert_eq!(h["packed"], -12345);
+/// assert_eq!(h["binary"], 256);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(tag = "type", rename_all = "lowercase")
+)]
+pub enum Codec {
+    /// Text in the encoding of the record. This is the default.
+    #[default]
+    Text,
+    /// Packed decimal (`COMP-3`). Each byte holds two digits, except for the last byte which
+    /// holds one digit and the sign. A field of `n` bytes holds up to `2n - 1` digits.
+    Packed {
+        /// Whether the field has a sign. Unsigned fields are written with an `F` sign nibble.
+        #[cfg_attr(feature = "schema", serde(default = "default_signed"))]
+        signed: bool,
+    },
+    /// Zoned decimal, as in an EBCDIC `DISPLAY` numeric item. Each byte holds one digit in its
+    /// low half, and the high half of the last byte holds the sign. Zones of `F` (EBCDIC) and `3`
+    /// (ASCII) are read as unsigned digits.
+    ///
+    /// Fields are written with the zones of the encoding of the record: `F` for digits, and `C`
+    /// and `D` for the signs, in EBCDIC; `3` for digits and positive signs, and `7` for negative
+    /// signs, otherwise.
+    Zoned {
+        /// Whether the field has a sign. Unsigned fields are written with a digit zone.
+        #[cfg_attr(feature = "schema", serde(default = "default_signed"))]
+        signed: bool,
+    },
+    /// Signed number whose last digit is overpunched with its sign: `{` and `A` to `I` for the
+    /// positive digits 0 to 9, and `}` and `J` to `R` for the negative ones. A plain last digit is
+    /// read as positive. The characters go through the encoding of the record, so this works for
+    /// both ASCII and EBCDIC data. With `Sign::Leading`, the first digit is overpunched instead, as
+    /// with COBOL `SIGN LEADING`.
+    Overpunch,
+    /// Binary integer (`COMP`, `COMP-4`, `COMP-5`, `BINARY`) of up to 16 bytes.
+    Binary {
+        /// The byte order of the integer.
+        #[cfg_attr(feature = "schema", serde(default))]
+        endian: Endian,
+        /// Whether the integer is two's complement signed.
+        #[cfg_attr(feature = "schema", serde(default = "default_signed"))]
+        signed: bool,
+    },
+}
+
+#[cfg(feature = "schema")]
+fn default_signed() -> bool {
+    true
+}
+
+/// The byte order of a binary integer field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Endian {
+    /// Most significant byte first, as written by mainframes. This is the default.
+    #[default]
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+/// Where the sign of a signed number is written in a text field.
+///
+/// Only text fields have a sign policy, the other codecs carry the sign themselves, except that
+/// `Sign::Leading` moves the sign of a `Codec::Overpunch` field to its first digit. Examples are
+/// for `-12` and `12` in a 5 byte field, right justified and padded with `0`.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{FieldSet, Justify, Sign, Writer};
+///
+/// let fields = FieldSet::new_field(0..5)
+///     .justify(Justify::Right)
+///     .pad_with('0')
+///     .sign(Sign::Trailing);
+///
+/// let mut wrtr = Writer::from_memory();
+/// fixed_width::to_writer_with_fields(&mut wrtr, &-12, fields.clone()).unwrap();
+///
+/// let bytes: Vec<u8> = wrtr.into();
+/// assert_eq!(bytes, b"0012-");
+///
+/// let n: i32 = fixed_width::from_bytes_with_fields(&bytes, fields).unwrap();
+/// assert_eq!(n, -12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "schema",
+    derive(serde_derive::Serialize, serde_derive::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Sign {
+    /// The sign is part of the text of the value and is padded along with it: `00-12`, `00012`.
+    /// This is the default.
+    #[default]
+    Inline,
+    /// A `-` is written before the padding of negative values: `-0012`, `00012`.
+    Leading,
+    /// A `-` is written after negative values: `0012-`, `00012`.
+    Trailing,
+    /// The last byte is a sign column that is always written, as with COBOL
+    /// `SIGN TRAILING SEPARATE`: `0012-`, `0012+`.
+    Separate,
+    /// A `+` or `-` is always written before the padding: `-0012`, `+0012`.
+    Always,
+}
+
+impl<T: AsRef<str>> From<T> for Sign {
+    fn from(s: T) -> Self {
+        match s.as_ref().to_lowercase().trim() {
+            "inline" => Sign::Inline,
+            "leading" => Sign::Leading,
+            "trailing" => Sign::Trailing,
+            "separate" => Sign::Separate,
+            "always" => Sign::Always,
+            _ => panic!("Sign must be 'inline', 'leading', 'trailing', 'separate' or 'always'"),
+        }
+    }
+}
+
+/// Decodes the bytes of a packed, zoned or binary field into the decimal text of its value. Text
+/// and overpunched fields are text in the encoding of the record, and are read as such.
+pub(crate) fn decode(codec: &Codec, bytes: &[u8]) -> Result<String, DeserializeError> {
+    match *codec {
+        Codec::Text | Codec::Overpunch => Err(DeserializeError::Unsupported(format!(
+            "{:?} fields are read as text",
+            codec
+        ))),
+        Codec::Packed { signed } => unpack(bytes, signed),
+        Codec::Zoned { signed } => unzone(bytes, signed),
+        Codec::Binary { endian, signed } => from_binary(bytes, endian, signed),
+    }
+}
+
+/// Encodes the decimal text of a value into the bytes of a packed, zoned or binary field of the
+/// given width, in a record of the given encoding. Text and overpunched fields are written as
+/// text.
+pub(crate) fn encode(
+    codec: &Codec,
+    text: &str,
+    width: usize,
+    encoding: Encoding,
+) -> Result<Vec<u8>, SerializeError> {
+    match *codec {
+        Codec::Text | Codec::Overpunch => Err(SerializeError::Unsupported(format!(
+            "{:?} fields are written as text",
+            codec
+        ))),
+        Codec::Packed { signed } => {
+            let (negative, digits) = parse_integer(text)?;
+            pack(negative, digits, signed, width, text)
+        }
+        Codec::Zoned { signed } => {
+            let (negative, digits) = parse_integer(text)?;
+            zone(negative, digits, signed, width, text, encoding)
+        }
+        Codec::Binary { endian, signed } => {
+            let (negative, digits) = parse_integer(text)?;
+            to_binary(negative, digits, endian, signed, width, text)
+        }
+    }
+}
+
+fn format(negative: bool, digits: &str) -> String {
+    let digits = digits.trim_start_matches('0');
+
+    match (negative, digits.is_empty()) {
+        (_, true) => "0".to_string(),
+        (true, false) => format!("-{}", digits),
+        (false, false) => digits.to_string(),
+    }
+}
+
+// Splits the text of an integer into its sign and digits, without leading zeros. A fractional
+// part is only accepted if it is zero.
+fn parse_integer(text: &str) -> Result<(bool, &str), SerializeError> {
+    let invalid = || SerializeError::InvalidNumber(text.to_string());
+    let trimmed = text.trim();
+
+    let (negative, unsigned) = match trimmed.as_bytes().first() {
+        Some(b'-') => (true, &trimmed[1..]),
+        Some(b'+') => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+
+    let (integer, fraction) = match unsigned.find('.') {
+        Some(i) => (&unsigned[..i], &unsigned[i + 1..]),
+        None => (unsigned, ""),
+    };
+
+    if integer.is_empty()
+        || !integer.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b == b'0')
+    {
+        return Err(invalid());
+    }
+
+    let digits = integer.trim_start_matches('0');
+    Ok((negative && !digits.is_empty(), digits))
+}
+
+fn unpack(bytes: &[u8], signed: bool) -> Result<String, DeserializeError> {
+    let invalid = |b: &u8| {
+        DeserializeError::InvalidNumber(format!("invalid packed decimal byte 0x{:02X}", b))
+    };
+
+    let (last, rest) = match bytes.split_last() {
+        Some(split) => split,
+        None => {
+            return Err(DeserializeError::InvalidNumber(
+                "empty packed decimal".to_string(),
+            ))
+        }
+    };
+
+    let mut digits = String::with_capacity(bytes.len() * 2);
+    for b in rest.iter().chain(std::iter::once(last)) {
+        for nibble in &[b >> 4, b & 0x0F] {
+            if digits.len() == bytes.len() * 2 - 1 {
+                break;
+            }
+            if *nibble > 9 {
+                return Err(invalid(b));
+            }
+            digits.push(char::from(b'0' + nibble));
+        }
+    }
+
+    let negative = match last & 0x0F {
+        0x0B | 0x0D => true,
+        0x0A | 0x0C | 0x0E | 0x0F => false,
+        _ => return Err(invalid(last)),
+    };
+
+    if negative && !signed {
+        return Err(DeserializeError::InvalidNumber(
+            "negative packed decimal in an unsigned field".to_string(),
+        ));
+    }
+
+    Ok(format(negative, &digits))
+}
+
+fn pack(
+    negative: bool,
+    digits: &str,
+    signed: bool,
+    width: usize,
+    text: &str,
+) -> Result<Vec<u8>, SerializeError> {
+    if negative && !signed {
+        return Err(SerializeError::InvalidNumber(text.to_string()));
+    }
+
+    let capacity = (width * 2).saturating_sub(1);
+    if digits.len() > capacity {
+        return Err(SerializeError::Overflow(text.to_string()));
+    }
+
+    let sign = match (signed, negative) {
+        (false, _) => 0x0F,
+        (true, false) => 0x0C,
+        (true, true) => 0x0D,
+    };
+
+    let mut nibbles: Vec<u8> = std::iter::repeat_n(0, capacity - digits.len())
+        .chain(digits.bytes().map(|b| b - b'0'))
+        .collect();
+    nibbles.push(sign);
+
+    Ok(nibbles.chunks(2).map(|n| (n[0] << 4) | n[1]).collect())
+}
+
+fn unzone(bytes: &[u8], signed: bool) -> Result<String, DeserializeError> {
+    let invalid =
+        |b: &u8| DeserializeError::InvalidNumber(format!("invalid zoned decimal byte 0x{:02X}", b));
+
+    let (last, rest) = match bytes.split_last() {
+        Some(split) => split,
+        None => {
+            return Err(DeserializeError::InvalidNumber(
+                "empty zoned decimal".to_string(),
+            ))
+        }
+    };
+
+    let mut digits = String::with_capacity(bytes.len());
+    for b in rest {
+        if !matches!(b >> 4, 0x0F | 0x03) || b & 0x0F > 9 {
+            return Err(invalid(b));
+        }
+        digits.push(char::from(b'0' + (b & 0x0F)));
+    }
+
+    if last & 0x0F > 9 {
+        return Err(invalid(last));
+    }
+    digits.push(char::from(b'0' + (last & 0x0F)));
+
+    let negative = match last >> 4 {
+        0x0B | 0x0D | 0x07 => true,
+        0x0A | 0x0C | 0x0E | 0x0F | 0x03 => false,
+        _ => return Err(invalid(last)),
+    };
+
+    if negative && !signed {
+        return Err(DeserializeError::InvalidNumber(
+            "negative zoned decimal in an unsigned field".to_string(),
+        ));
+    }
+
+    Ok(format(negative, &digits))
+}
+
+fn zone(
+    negative: bool,
+    digits: &str,
+    signed: bool,
+    width: usize,
+    text: &str,
+    encoding: Encoding,
+) -> Result<Vec<u8>, SerializeError> {
+    if negative && !signed {
+        return Err(SerializeError::InvalidNumber(text.to_string()));
+    }
+
+    if digits.len() > width || width == 0 {
+        return Err(SerializeError::Overflow(text.to_string()));
+    }
+
+    // The zone of the digits is the high half of the encoded `0`: `F` in EBCDIC, `3` otherwise.
+    let digit = encoding.encode_char('0').unwrap_or(b'0') & 0xF0;
+    let sign = match (signed, negative, digit) {
+        (false, _, _) => digit,
+        (true, false, 0xF0) => 0xC0,
+        (true, true, 0xF0) => 0xD0,
+        (true, false, _) => digit,
+        (true, true, _) => 0x70,
+    };
+
+    let mut bytes: Vec<u8> = std::iter::repeat_n(digit, width - digits.len())
+        .chain(digits.bytes().map(|b| digit | (b - b'0')))
+        .collect();
+
+    if let Some(last) = bytes.last_mut() {
+        *last = sign | (*last & 0x0F);
+    }
+
+    Ok(bytes)
+}
+
+/// Places the implied decimal point of the digits of a field, so that `-1234` with a scale of
+/// `2` becomes `-12.34`. Empty text is left empty.
+pub(crate) fn scale(text: &str, scale: usize) -> Result<String, DeserializeError> {
+    if text.is_empty() {
+        return Ok(String::new());
+    }
+
+    let (negative, digits) = match text.as_bytes()[0] {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DeserializeError::InvalidNumber(format!(
+            "`{}` is not a number with an implied decimal point",
+            text
+        )));
+    }
+
+    let negative = negative && digits.bytes().any(|b| b != b'0');
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    let sign = if negative { "-" } else { "" };
+
+    Ok(format!("{}{}.{}", sign, format(false, integer), fraction))
+}
+
+/// Returns the integer part of a scaled number, which must not have a fraction.
+pub(crate) fn integer_part(text: &str) -> Result<&str, DeserializeError> {
+    match text.find('.') {
+        Some(i) if text[i + 1..].bytes().all(|b| b == b'0') => Ok(&text[..i]),
+        Some(_) => Err(DeserializeError::InvalidNumber(format!(
+            "`{}` can not be read as an integer without rounding",
+            text
+        ))),
+        None => Ok(text),
+    }
+}
+
+/// Removes the implied decimal point from the text of a value, so that `12.3` with a scale of
+/// `2` becomes `1230`. Fails if the value has more decimals than the scale.
+pub(crate) fn unscale(text: &str, scale: usize) -> Result<String, SerializeError> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+
+    let (negative, unsigned) = match trimmed.as_bytes()[0] {
+        b'-' => (true, &trimmed[1..]),
+        b'+' => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+
+    let (integer, fraction) = match unsigned.find('.') {
+        Some(i) => (&unsigned[..i], &unsigned[i + 1..]),
+        None => (unsigned, ""),
+    };
+
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(SerializeError::InvalidNumber(text.to_string()));
+    }
+
+    let (kept, dropped) = fraction.split_at(fraction.len().min(scale));
+    if !dropped.bytes().all(|b| b == b'0') {
+        return Err(SerializeError::Inexact(text.to_string(), scale));
+    }
+
+    let digits = format!("{}{:0<width$}", integer, kept, width = scale);
+    Ok(format(negative, &digits))
+}
+
+/// Splits the text of a number into the sign written before the padding, the unsigned text to
+/// pad, and the sign written after the padding. Returns `None` if the sign is part of the text,
+/// either because of the sign policy or because the text is not a number.
+pub(crate) fn place_sign(text: &str, sign: Sign) -> Option<(&'static str, &str, &'static str)> {
+    let (negative, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+
+    if sign == Sign::Inline
+        || unsigned.is_empty()
+        || !unsigned.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+    {
+        return None;
+    }
+
+    Some(match (sign, negative) {
+        (Sign::Leading, true) | (Sign::Always, true) => ("-", unsigned, ""),
+        (Sign::Always, false) => ("+", unsigned, ""),
+        (Sign::Trailing, true) | (Sign::Separate, true) => ("", unsigned, "-"),
+        (Sign::Separate, false) => ("", unsigned, "+"),
+        _ => ("", unsigned, ""),
+    })
+}
+
+/// Moves the sign of the text of a field to the front of its digits, removing the padding in
+/// between, so that `-  12` and `0012-` are read as `-12` and `-0012`.
+pub(crate) fn unplace_sign(text: &str, sign: Sign) -> String {
+    let (negative, unsigned) = match sign {
+        Sign::Inline => return text.to_string(),
+        Sign::Leading | Sign::Always => match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        },
+        Sign::Trailing | Sign::Separate => match text.as_bytes().last() {
+            Some(b'-') => (true, &text[..text.len() - 1]),
+            Some(b'+') => (false, &text[..text.len() - 1]),
+            _ => (false, text),
+        },
+    };
+
+    match negative {
+        true => format!("-{}", unsigned.trim()),
+        false => unsigned.trim().to_string(),
+    }
+}
+
+const POSITIVE_PUNCHES: &[u8; 10] = b"{ABCDEFGHI";
+const NEGATIVE_PUNCHES: &[u8; 10] = b"}JKLMNOPQR";
+
+/// Reads the text of an overpunched number into the decimal text of its value. The sign is carried
+/// by the first digit if `leading`, and by the last digit otherwise.
+pub(crate) fn unpunch(text: &str, leading: bool) -> Result<String, DeserializeError> {
+    let invalid =
+        || DeserializeError::InvalidNumber(format!("invalid overpunched number `{}`", text));
+
+    let bytes = text.as_bytes();
+    let at = match leading {
+        _ if bytes.is_empty() => return Err(invalid()),
+        true => 0,
+        false => bytes.len() - 1,
+    };
+
+    if !bytes
+        .iter()
+        .enumerate()
+        .all(|(i, b)| i == at || b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let punched = bytes[at];
+    let (negative, digit) = match punched {
+        b'0'..=b'9' => (false, punched),
+        _ => match (
+            POSITIVE_PUNCHES.iter().position(|&b| b == punched),
+            NEGATIVE_PUNCHES.iter().position(|&b| b == punched),
+        ) {
+            (Some(d), _) => (false, b'0' + d as u8),
+            (_, Some(d)) => (true, b'0' + d as u8),
+            (None, None) => return Err(invalid()),
+        },
+    };
+
+    let digits: String = bytes
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| char::from(if i == at { digit } else { b }))
+        .collect();
+
+    Ok(format(negative, &digits))
+}
+
+/// Writes the decimal text of a value as an overpunched number of the given width, with the sign
+/// carried by the first digit if `leading`, and by the last digit otherwise.
+pub(crate) fn punch(text: &str, width: usize, leading: bool) -> Result<String, SerializeError> {
+    let (negative, digits) = parse_integer(text)?;
+
+    if digits.len() > width || width == 0 {
+        return Err(SerializeError::Overflow(text.to_string()));
+    }
+
+    let mut punched = format!("{:0>width$}", digits, width = width).into_bytes();
+    let punches = if negative {
+        NEGATIVE_PUNCHES
+    } else {
+        POSITIVE_PUNCHES
+    };
+
+    let at = if leading { 0 } else { width - 1 };
+    punched[at] = punches[usize::from(punched[at] - b'0')];
+
+    Ok(punched.into_iter().map(char::from).collect())
+}
+
+fn from_binary(bytes: &[u8], endian: Endian, signed: bool) -> Result<String, DeserializeError> {
+    if bytes.is_empty() || bytes.len() > 16 {
+        return Err(DeserializeError::InvalidNumber(format!(
+            "binary fields must be 1 to 16 bytes wide, got {}",
+            bytes.len()
+        )));
+    }
+
+    let mut ordered = bytes.to_vec();
+    if endian == Endian::Little {
+        ordered.reverse();
+    }
+
+    let mut value = ordered
+        .iter()
+        .fold(0_u128, |v, b| (v << 8) | u128::from(*b));
+
+    if signed && ordered[0] & 0x80 != 0 {
+        if ordered.len() < 16 {
+            value |= !0_u128 << (ordered.len() * 8);
+        }
+        Ok((value as i128).to_string())
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn to_binary(
+    negative: bool,
+    digits: &str,
+    endian: Endian,
+    signed: bool,
+    width: usize,
+    text: &str,
+) -> Result<Vec<u8>, SerializeError> {
+    if width == 0 || width > 16 {
+        return Err(SerializeError::Message(format!(
+            "binary fields must be 1 to 16 bytes wide, got {}",
+            width
+        )));
+    }
+
+    if negative && !signed {
+        return Err(SerializeError::InvalidNumber(text.to_string()));
+    }
+
+    let overflow = || SerializeError::Overflow(text.to_string());
+    let magnitude: u128 = match digits {
+        "" => 0,
+        digits => digits.parse().map_err(|_| overflow())?,
+    };
+
+    let bits = width * 8;
+    let fits = match (signed, negative) {
+        (false, _) => bits == 128 || magnitude < 1 << bits,
+        (true, false) => magnitude < 1 << (bits - 1),
+        (true, true) => magnitude <= 1 << (bits - 1),
+    };
+
+    if !fits {
+        return Err(overflow());
+    }
+
+    let value = if negative {
+        (!magnitude).wrapping_add(1)
+    } else {
+        magnitude
+    };
+
+    let mut bytes = value.to_be_bytes()[16 - width..].to_vec();
+    if endian == Endian::Little {
+        bytes.reverse();
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PACKED: Codec = Codec::Packed { signed: true };
+
+    #[test]
+    fn packed_decimal() {
+        assert_eq!(decode(&PACKED, &[0x12, 0x34, 0x5C]).unwrap(), "12345");
+        assert_eq!(decode(&PACKED, &[0x00, 0x12, 0x3D]).unwrap(), "-123");
+        assert_eq!(decode(&PACKED, &[0x00, 0x0D]).unwrap(), "0");
+        assert_eq!(
+            decode(&Codec::Packed { signed: false }, &[0x99, 0x9F]).unwrap(),
+            "999"
+        );
+
+        assert!(decode(&PACKED, &[0x1A, 0x2C]).is_err());
+        assert!(decode(&PACKED, &[0x12, 0x34]).is_err());
+        assert!(decode(&Codec::Packed { signed: false }, &[0x1D]).is_err());
+        assert!(decode(&Codec::Overpunch, b"12C").is_err());
+        assert!(encode(&Codec::Text, "12", 2, Encoding::Utf8).is_err());
+
+        assert_eq!(
+            encode(&PACKED, "12345", 3, Encoding::Utf8).unwrap(),
+            vec![0x12, 0x34, 0x5C]
+        );
+        assert_eq!(
+            encode(&PACKED, "-123", 3, Encoding::Utf8).unwrap(),
+            vec![0x00, 0x12, 0x3D]
+        );
+        assert_eq!(
+            encode(&PACKED, "-0", 2, Encoding::Utf8).unwrap(),
+            vec![0x00, 0x0C]
+        );
+        assert_eq!(
+            encode(&PACKED, "42.000", 2, Encoding::Utf8).unwrap(),
+            vec![0x04, 0x2C]
+        );
+        assert_eq!(
+            encode(&Codec::Packed { signed: false }, "7", 1, Encoding::Utf8).unwrap(),
+            vec![0x7F]
+        );
+
+        assert!(matches!(
+            encode(&PACKED, "1234", 2, Encoding::Utf8),
+            Err(SerializeError::Overflow(_))
+        ));
+        assert!(matches!(
+            encode(&PACKED, "1.5", 2, Encoding::Utf8),
+            Err(SerializeError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            encode(&Codec::Packed { signed: false }, "-1", 2, Encoding::Utf8),
+            Err(SerializeError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn zoned_decimal() {
+        let zoned = Codec::Zoned { signed: true };
+
+        assert_eq!(decode(&zoned, &[0xF1, 0xF2, 0xC3]).unwrap(), "123");
+        assert_eq!(decode(&zoned, &[0xF0, 0xF4, 0xD2]).unwrap(), "-42");
+        assert_eq!(decode(&zoned, b"007").unwrap(), "7");
+        assert!(decode(&zoned, &[0xC1, 0xF2]).is_err());
+        assert!(decode(&Codec::Zoned { signed: false }, &[0xD1]).is_err());
+
+        assert_eq!(
+            encode(&zoned, "123", 3, Encoding::Cp037).unwrap(),
+            vec![0xF1, 0xF2, 0xC3]
+        );
+        assert_eq!(
+            encode(&zoned, "-42", 3, Encoding::Cp037).unwrap(),
+            vec![0xF0, 0xF4, 0xD2]
+        );
+        assert_eq!(
+            encode(&Codec::Zoned { signed: false }, "5", 2, Encoding::Cp037).unwrap(),
+            vec![0xF0, 0xF5]
+        );
+        assert!(matches!(
+            encode(&zoned, "1234", 3, Encoding::Cp037),
+            Err(SerializeError::Overflow(_))
+        ));
+
+        for (value, bytes) in [("123", &b"123"[..]), ("-42", b"04r"), ("0", b"000")] {
+            let encoded = encode(&zoned, value, 3, Encoding::Utf8).unwrap();
+            assert_eq!(encoded, bytes);
+            assert_eq!(decode(&zoned, &encoded).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn overpunched_numbers() {
+        assert_eq!(unpunch("0012C", false).unwrap(), "123");
+        assert_eq!(unpunch("0012L", false).unwrap(), "-123");
+        assert_eq!(unpunch("00{", false).unwrap(), "0");
+        assert_eq!(unpunch("1}", false).unwrap(), "-10");
+        assert_eq!(unpunch("42", false).unwrap(), "42");
+        assert!(unpunch("", false).is_err());
+        assert!(unpunch("1A2", false).is_err());
+        assert!(unpunch("12S", false).is_err());
+
+        assert_eq!(punch("123", 5, false).unwrap(), "0012C");
+        assert_eq!(punch("-123", 5, false).unwrap(), "0012L");
+        assert_eq!(punch("-10", 2, false).unwrap(), "1}");
+        assert_eq!(punch("0", 3, false).unwrap(), "00{");
+        assert!(matches!(
+            punch("123", 2, false),
+            Err(SerializeError::Overflow(_))
+        ));
+        assert!(matches!(
+            punch("12.5", 4, false),
+            Err(SerializeError::InvalidNumber(_))
+        ));
+
+        assert_eq!(unpunch("J123", true).unwrap(), "-1123");
+        assert_eq!(unpunch("{012", true).unwrap(), "12");
+        assert!(unpunch("12J", true).is_err());
+        assert_eq!(punch("-123", 5, true).unwrap(), "}0123");
+        assert_eq!(punch("7", 1, true).unwrap(), "G");
+    }
+
+    #[test]
+    fn implied_decimals() {
+        assert_eq!(scale("0001234567", 2).unwrap(), "12345.67");
+        assert_eq!(scale("-5", 3).unwrap(), "-0.005");
+        assert_eq!(scale("-000", 2).unwrap(), "0.00");
+        assert_eq!(scale("", 2).unwrap(), "");
+        assert!(scale("12.34", 2).is_err());
+
+        assert_eq!(integer_part("12.00").unwrap(), "12");
+        assert!(integer_part("12.01").is_err());
+
+        assert_eq!(unscale("12345.67", 2).unwrap(), "1234567");
+        assert_eq!(unscale("-0.5", 2).unwrap(), "-50");
+        assert_eq!(unscale("42", 2).unwrap(), "4200");
+        assert_eq!(unscale("1.2300", 2).unwrap(), "123");
+        assert_eq!(unscale(".5", 1).unwrap(), "5");
+        assert!(matches!(
+            unscale("1.234", 2),
+            Err(SerializeError::Inexact(_, 2))
+        ));
+        assert!(matches!(
+            unscale("1e5", 2),
+            Err(SerializeError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn sign_placement() {
+        assert_eq!(place_sign("-12", Sign::Leading), Some(("-", "12", "")));
+        assert_eq!(place_sign("12", Sign::Leading), Some(("", "12", "")));
+        assert_eq!(place_sign("-1.5", Sign::Trailing), Some(("", "1.5", "-")));
+        assert_eq!(place_sign("12", Sign::Separate), Some(("", "12", "+")));
+        assert_eq!(place_sign("+12", Sign::Always), Some(("+", "12", "")));
+        assert_eq!(place_sign("-12", Sign::Inline), None);
+        assert_eq!(place_sign("abc", Sign::Leading), None);
+        assert_eq!(place_sign("", Sign::Always), None);
+
+        assert_eq!(unplace_sign("-  12", Sign::Leading), "-12");
+        assert_eq!(unplace_sign("+0012", Sign::Always), "0012");
+        assert_eq!(unplace_sign("0012-", Sign::Trailing), "-0012");
+        assert_eq!(unplace_sign("  12+", Sign::Separate), "12");
+        assert_eq!(unplace_sign("00-12", Sign::Inline), "00-12");
+        assert_eq!(unplace_sign("", Sign::Trailing), "");
+    }
+
+    #[test]
+    fn binary_integers() {
+        let be = Codec::Binary {
+            endian: Endian::Big,
+            signed: true,
+        };
+        let le = Codec::Binary {
+            endian: Endian::Little,
+            signed: true,
+        };
+        let unsigned = Codec::Binary {
+            endian: Endian::Big,
+            signed: false,
+        };
+
+        assert_eq!(decode(&be, &[0x01, 0x00]).unwrap(), "256");
+        assert_eq!(decode(&le, &[0x01, 0x00]).unwrap(), "1");
+        assert_eq!(decode(&be, &[0xFF, 0xFE]).unwrap(), "-2");
+        assert_eq!(decode(&unsigned, &[0xFF, 0xFE]).unwrap(), "65534");
+        assert_eq!(
+            decode(&be, &[0x80; 16]).unwrap(),
+            i128::from_be_bytes([0x80; 16]).to_string()
+        );
+        assert!(decode(&be, &[0; 17]).is_err());
+
+        assert_eq!(
+            encode(&be, "-2", 2, Encoding::Utf8).unwrap(),
+            vec![0xFF, 0xFE]
+        );
+        assert_eq!(
+            encode(&le, "258", 4, Encoding::Utf8).unwrap(),
+            vec![0x02, 0x01, 0x00, 0x00]
+        );
+        assert_eq!(encode(&be, "-128", 1, Encoding::Utf8).unwrap(), vec![0x80]);
+        assert_eq!(
+            encode(&unsigned, "255", 1, Encoding::Utf8).unwrap(),
+            vec![0xFF]
+        );
+        assert_eq!(
+            encode(&unsigned, &u128::MAX.to_string(), 16, Encoding::Utf8).unwrap(),
+            vec![0xFF; 16]
+        );
+
+        assert!(matches!(
+            encode(&be, "128", 1, Encoding::Utf8),
+            Err(SerializeError::Overflow(_))
+        ));
+        assert!(matches!(
+            encode(&be, "-129", 1, Encoding::Utf8),
+            Err(SerializeError::Overflow(_))
+        ));
+        assert!(matches!(
+            encode(&unsigned, "256", 1, Encoding::Utf8),
+            Err(SerializeError::Overflow(_))
+        ));
+        assert!(matches!(
+            encode(&unsigned, "-1", 1, Encoding::Utf8),
+            Err(SerializeError::InvalidNumber(_))
+        ));
+    }
+}
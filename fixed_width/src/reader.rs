@@ -1,7 +1,12 @@
-use crate::{error::Error, LineBreak, Result};
+use crate::{
+    error::Error, DeserializeError, Deserializer, Encoding, FieldSet, FixedWidth, LineBreak,
+    RecordTypes, Result, Structure, Trailing,
+};
+use serde::de::DeserializeOwned;
 use std::{
-    fs,
-    io::{self, Read},
+    fmt, fs,
+    io::{self, BufRead, Read},
+    marker::PhantomData,
     path::Path,
 };
 
@@ -21,6 +26,96 @@ pub struct StringReader<'a, R: 'a> {
     r: &'a mut Reader<R>,
 }
 
+/// An iterator of records read as one of their record types.
+///
+/// The lifetime 'a denotes the lifetime of the reader, R, and of the record types.
+pub struct Records<'a, R: 'a, T: 'a> {
+    r: &'a mut Reader<R>,
+    types: &'a RecordTypes<T>,
+}
+
+/// An iterator of records deserialized into `T`.
+///
+/// The lifetime 'a denotes the lifetime of the reader, R.
+pub struct DeserializeRecords<'a, R: 'a, T> {
+    r: &'a mut Reader<R>,
+    fields: FieldSet,
+    record: PhantomData<T>,
+}
+
+/// The errors of an invalid record, found by `Reader::validate`.
+#[derive(Debug)]
+pub struct RecordErrors {
+    index: usize,
+    offset: u64,
+    errors: Vec<DeserializeError>,
+}
+
+impl RecordErrors {
+    /// The index of the record, counted from `0`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The offset of the first byte of the record in the data.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The errors of the invalid fields of the record. The last error may instead be one that
+    /// stopped the record from being read at all, such as the record running out of fields.
+    pub fn errors(&self) -> &[DeserializeError] {
+        &self.errors
+    }
+}
+
+/// The result of validating every record of the data, see `Reader::validate`.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    records: usize,
+    invalid: Vec<RecordErrors>,
+}
+
+impl ValidationReport {
+    /// The number of records read.
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// The records that have at least one error, in the order they were read.
+    pub fn invalid(&self) -> &[RecordErrors] {
+        &self.invalid
+    }
+
+    /// Returns `true` if every record is valid.
+    pub fn is_valid(&self) -> bool {
+        self.invalid.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} records are invalid",
+            self.invalid.len(),
+            self.records
+        )?;
+
+        for record in &self.invalid {
+            for error in &record.errors {
+                write!(
+                    f,
+                    "\nrecord {} at byte {}: {}",
+                    record.index, record.offset, error
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// A fixed width data reader. It parses fixed width data and provides the data via iterators.
 ///
 /// ### Example
@@ -53,9 +148,7 @@ pub struct StringReader<'a, R: 'a> {
 /// let data = "foobar1234foobaz6789";
 /// let mut reader = Reader::from_string(data).width(10);
 ///
-/// for row in reader.byte_reader().filter_map(result::Result::ok) {
-///     let record: Foo = fixed_width::from_bytes(&row).unwrap();
-///
+/// for record in reader.deserialize::<Foo>().filter_map(result::Result::ok) {
 ///     println!("{}", record.name);
 ///     println!("{}", record.age);
 /// }
@@ -80,7 +173,7 @@ pub struct StringReader<'a, R: 'a> {
 ///
 ///  for row in reader.byte_reader() {
 ///      let bytes = row.unwrap();
-///      let mut de = Deserializer::new(&bytes, fields.clone());
+///      let mut de = Deserializer::with_fields(&bytes, &fields);
 ///      let record: HashMap<String, String> = HashMap::deserialize(&mut de).unwrap();
 ///
 ///      println!("{}", record.get("name").unwrap());
@@ -144,10 +237,19 @@ pub struct Reader<R> {
     buf: Vec<u8>,
     linebreak_buf: Vec<u8>,
     eof: bool,
+    index: usize,
+    position: u64,
+    leftover: usize,
     /// The width in bytes of the record. Required in order to parse.
     pub record_width: usize,
     /// The line break that occurs between each record. Defaults to `LineBreak::None`
     pub linebreak: LineBreak,
+    /// The encoding of the text in each record. Defaults to `Encoding::Utf8`
+    pub encoding: Encoding,
+    /// What to do with a partial record at the end of the data. Defaults to `Trailing::Error`
+    pub trailing: Trailing,
+    /// The byte that pads lines shorter than the record width, see `ragged`. Defaults to `None`
+    pub ragged: Option<u8>,
 }
 
 impl<R> Reader<R>
@@ -163,11 +265,18 @@ where
             linebreak: LineBreak::None,
             linebreak_buf: Vec::new(),
             eof: false,
+            index: 0,
+            position: 0,
+            leftover: 0,
+            encoding: Encoding::default(),
+            trailing: Trailing::default(),
+            ragged: None,
         }
     }
 
-    /// Reads each record of the data as a `String`. If the data is not valid UTF-8, then
-    /// you should use `byte_reader` instead.
+    /// Reads each record of the data as a `String`, decoded from the reader's encoding. If the
+    /// encoding is UTF-8, invalid sequences are replaced with `U+FFFD`. If the data contains
+    /// binary fields, then you should use `byte_reader` instead.
     ///
     /// ### Example
     ///
@@ -180,7 +289,7 @@ where
     ///     assert_eq!(record.unwrap(), "abcd1234")
     /// }
     /// ```
-    pub fn string_reader(&mut self) -> StringReader<R> {
+    pub fn string_reader(&mut self) -> StringReader<'_, R> {
         StringReader { r: self }
     }
 
@@ -197,10 +306,221 @@ where
     ///     assert_eq!(record.unwrap(), b"abcd1234".to_vec())
     /// }
     /// ```
-    pub fn byte_reader(&mut self) -> ByteReader<R> {
+    pub fn byte_reader(&mut self) -> ByteReader<'_, R> {
         ByteReader { r: self }
     }
 
+    /// Deserializes each record into `T`, using the fields of its `FixedWidth` implementation and
+    /// the encoding of the reader. Errors are returned as `Error::RecordError`, with the index and
+    /// the byte offset of the record they occurred in.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Error, FieldSet, FixedWidth, Reader};
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// impl FixedWidth for Person {
+    ///     fn fields() -> FieldSet {
+    ///         FieldSet::Seq(vec![FieldSet::new_field(0..4), FieldSet::new_field(4..6)])
+    ///     }
+    /// }
+    ///
+    /// let mut reader = Reader::from_string("Ann 32Bob xx").width(6);
+    /// let mut people = reader.deserialize::<Person>();
+    ///
+    /// assert_eq!(people.next().unwrap().unwrap().age, 32);
+    ///
+    /// match people.next() {
+    ///     Some(Err(Error::RecordError(index, offset, _))) => assert_eq!((index, offset), (1, 6)),
+    ///     _ => panic!("expected an error in the second record"),
+    /// }
+    /// ```
+    pub fn deserialize<T>(&mut self) -> DeserializeRecords<'_, R, T>
+    where
+        T: FixedWidth + DeserializeOwned,
+    {
+        self.deserialize_with_fields(T::fields())
+    }
+
+    /// Deserializes each record into `T` using the given fields, see `deserialize`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Reader};
+    /// use std::collections::HashMap;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..4).name("name"),
+    ///     FieldSet::new_field(4..6).name("age"),
+    /// ]);
+    /// let mut reader = Reader::from_string("Ann 32Bob 41").width(6);
+    /// let people: Vec<HashMap<String, String>> = reader
+    ///     .deserialize_with_fields(fields)
+    ///     .map(Result::unwrap)
+    ///     .collect();
+    ///
+    /// assert_eq!(people[1]["name"], "Bob");
+    /// ```
+    pub fn deserialize_with_fields<T>(&mut self, fields: FieldSet) -> DeserializeRecords<'_, R, T>
+    where
+        T: DeserializeOwned,
+    {
+        DeserializeRecords {
+            r: self,
+            fields,
+            record: PhantomData,
+        }
+    }
+
+    /// Reads every record into `T` in lenient mode, and reports each invalid field of each record
+    /// rather than stopping at the first one, see `Deserializer::lenient`. A record that cannot be
+    /// read, such as a partial final record, is reported as invalid. Only errors reading the data
+    /// itself, such as IO errors, are returned as an `Err`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, FixedWidth, Reader};
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     age: u8,
+    ///     height: u8,
+    /// }
+    ///
+    /// impl FixedWidth for Person {
+    ///     fn fields() -> FieldSet {
+    ///         FieldSet::Seq(vec![
+    ///             FieldSet::new_field(0..4).name("name"),
+    ///             FieldSet::new_field(4..6).name("age"),
+    ///             FieldSet::new_field(6..9).name("height"),
+    ///         ])
+    ///     }
+    /// }
+    ///
+    /// let mut reader = Reader::from_string("Ann 32170Bob xxyyyCat 41180").width(9);
+    /// let report = reader.validate::<Person>().unwrap();
+    ///
+    /// assert_eq!(report.records(), 3);
+    /// assert_eq!(report.invalid().len(), 1);
+    /// assert_eq!(report.invalid()[0].index(), 1);
+    /// assert_eq!(report.invalid()[0].errors().len(), 2);
+    /// ```
+    pub fn validate<T>(&mut self) -> Result<ValidationReport>
+    where
+        T: FixedWidth + DeserializeOwned,
+    {
+        self.validate_with_fields::<T>(T::fields())
+    }
+
+    /// Reads every record into `T` using the given fields, and reports each invalid field of each
+    /// record, see `validate`.
+    pub fn validate_with_fields<T>(&mut self, fields: FieldSet) -> Result<ValidationReport>
+    where
+        T: DeserializeOwned,
+    {
+        let encoding = self.encoding;
+        let mut report = ValidationReport::default();
+
+        loop {
+            let (index, offset) = (self.index, self.position);
+            let record = match self.next_record() {
+                Some(Ok(record)) => record,
+                // A record that cannot be read, such as a partial final record, is invalid.
+                Some(Err(Error::DeserializeError(e))) => {
+                    report.records += 1;
+                    report.invalid.push(RecordErrors {
+                        index,
+                        offset,
+                        errors: vec![e],
+                    });
+                    continue;
+                }
+                Some(Err(e)) => return Err(e),
+                None => break,
+            };
+
+            let mut de = Deserializer::with_fields(record, &fields)
+                .encoding(encoding)
+                .lenient(true);
+            let result = T::deserialize(&mut de);
+            let mut errors = de.into_errors();
+            if let Err(e) = result {
+                errors.push(e);
+            }
+
+            report.records += 1;
+            if !errors.is_empty() {
+                report.invalid.push(RecordErrors {
+                    index,
+                    offset,
+                    errors,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Reads each record as the record type of its code, see `RecordTypes`. Records are read in
+    /// the encoding of the reader, unless the record types have their own. Errors are returned as
+    /// `Error::RecordError`, with the index and the byte offset of the record they occurred in.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Reader, RecordTypes};
+    ///
+    /// let fields = FieldSet::Seq(vec![FieldSet::new_field(0..1), FieldSet::new_field(1..4)]);
+    /// let types = RecordTypes::new(0..1)
+    ///     .record_with_fields("A", fields.clone(), |(_, n): (String, u32)| n)
+    ///     .record_with_fields("B", fields, |(_, n): (String, u32)| n * 100);
+    ///
+    /// let mut reader = Reader::from_string("A001B002").width(4);
+    /// let values: Vec<u32> = reader.records(&types).map(Result::unwrap).collect();
+    ///
+    /// assert_eq!(values, vec![1, 200]);
+    /// ```
+    pub fn records<'a, T>(&'a mut self, types: &'a RecordTypes<T>) -> Records<'a, R, T> {
+        Records { r: self, types }
+    }
+
+    /// Validates every record against a `Structure`, see `Structure::validate`. Records are read
+    /// in the encoding of the reader, unless the structure has its own.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Encoding, Group, Reader, Structure};
+    ///
+    /// let structure = Structure::new(0..1, Group::new("H", "T").record("D")).unwrap();
+    ///
+    /// // "HDDT" in EBCDIC code page 037.
+    /// let data = vec![0xC8, 0xC4, 0xC4, 0xE3];
+    /// let mut reader = Reader::from_bytes(data).width(1).encoding(Encoding::Cp037);
+    ///
+    /// assert!(reader.validate_structure(&structure).is_ok());
+    /// ```
+    pub fn validate_structure(&mut self, structure: &Structure) -> Result<()> {
+        let mut validator = structure.validator_in(self.encoding);
+
+        while let Some(record) = self.next_record() {
+            validator.push(record?)?;
+        }
+
+        validator.finish()
+    }
+
     /// Reads the next record as a byte slice
     ///
     /// ### Example
@@ -225,9 +545,16 @@ where
             return None;
         }
 
+        if let Some(pad) = self.ragged {
+            return self.next_line(pad);
+        }
+
         match self.fill_buf() {
             Ok(0) => return None,
-            Ok(_) => {}
+            Ok(n) => {
+                self.index += 1;
+                self.position += n as u64;
+            }
             Err(e) => return Some(Err(e)),
         }
 
@@ -297,25 +624,170 @@ where
         self
     }
 
+    /// Sets what to do with bytes at the end of the data that are too few to make a whole record.
+    /// Defaults to `Trailing::Error`, so that truncated data is not mistaken for complete data.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Reader, Trailing};
+    ///
+    /// let mut reader = Reader::from_string("foobarba").width(3);
+    /// let records: Vec<_> = reader.byte_reader().collect();
+    ///
+    /// assert_eq!(records.len(), 3);
+    /// assert!(records[2].is_err());
+    /// assert_eq!(reader.leftover(), 2);
+    ///
+    /// let mut reader = Reader::from_string("foobarba").width(3).trailing(Trailing::Pad(b'_'));
+    /// let records: Vec<_> = reader.string_reader().map(Result::unwrap).collect();
+    ///
+    /// assert_eq!(records, vec!["foo", "bar", "ba_"]);
+    /// ```
+    pub fn trailing(mut self, trailing: Trailing) -> Self {
+        self.trailing = trailing;
+        self
+    }
+
+    /// Reads the data line by line, splitting it on the line break before reading each record.
+    /// Lines shorter than the record width are padded with the `pad` byte, so that fields missing
+    /// at the end of a line read as blank, and lines longer than the record width fail with
+    /// `DeserializeError::LongRecord`. This suits files whose trailing spaces have been stripped.
+    ///
+    /// Lines are split on the line break as written in the encoding of the reader, such as `0x25`
+    /// for a newline in `Encoding::Cp037`. The last line is padded like any other, so `trailing`
+    /// does not apply. Reading fails if the line break is `LineBreak::None`, since the data then
+    /// has no lines.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{LineBreak, Reader};
+    ///
+    /// let data = "foo bar\nbaz\nqux qu";
+    /// let mut reader = Reader::from_string(data)
+    ///     .width(7)
+    ///     .linebreak(LineBreak::Newline)
+    ///     .ragged(b' ');
+    /// let records: Vec<_> = reader.string_reader().map(Result::unwrap).collect();
+    ///
+    /// assert_eq!(records, vec!["foo bar", "baz    ", "qux qu "]);
+    /// ```
+    pub fn ragged(mut self, pad: u8) -> Self {
+        self.ragged = Some(pad);
+        self
+    }
+
+    /// The number of bytes at the end of the data that were too few to make a whole record, once
+    /// the data has been read. They are handled according to `trailing`.
+    pub fn leftover(&self) -> usize {
+        self.leftover
+    }
+
+    /// Sets the encoding of the text in each record. Defaults to `Encoding::Utf8`. This is used
+    /// by `string_reader` to decode records, and should be passed on to a `Deserializer` that
+    /// reads the records.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Encoding, Reader};
+    /// use std::result;
+    ///
+    /// // "ABCDEF" in EBCDIC code page 037.
+    /// let data = vec![0xC1, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6];
+    /// let mut reader = Reader::from_bytes(data).width(3).encoding(Encoding::Cp037);
+    /// let records: Vec<String> = reader.string_reader().filter_map(result::Result::ok).collect();
+    ///
+    /// assert_eq!(records, vec!["ABC".to_string(), "DEF".to_string()]);
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
     #[inline]
     fn has_linebreak(&self) -> bool {
         !matches!(self.linebreak, LineBreak::None)
     }
 
+    // Reads the next record into the buffer, and returns the number of bytes read from the data.
     #[inline]
     fn fill_buf(&mut self) -> Result<usize> {
-        match self.rdr.read_exact(&mut self.buf) {
-            Ok(_) => Ok(self.record_width),
-            Err(e) => match e.kind() {
-                io::ErrorKind::UnexpectedEof => {
-                    self.eof = true;
-                    Ok(0)
-                }
-                _ => Err(Error::from(e)),
-            },
+        let mut filled = 0;
+
+        while filled < self.buf.len() {
+            match self.rdr.read(&mut self.buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::from(e)),
+            }
+        }
+
+        if filled == self.buf.len() {
+            return Ok(filled);
+        }
+
+        self.eof = true;
+        self.leftover = filled;
+
+        match self.trailing {
+            _ if filled == 0 => Ok(0),
+            Trailing::Error => Err(DeserializeError::PartialRecord(filled).into()),
+            Trailing::Short => {
+                self.buf.truncate(filled);
+                Ok(filled)
+            }
+            Trailing::Pad(byte) => {
+                self.buf[filled..].fill(byte);
+                Ok(filled)
+            }
+            Trailing::Ignore => Ok(0),
         }
     }
 
+    // Reads the next line into the buffer, padded to the record width.
+    fn next_line(&mut self, pad: u8) -> Option<Result<&[u8]>> {
+        if !self.has_linebreak() {
+            self.eof = true;
+            let message = "ragged lines cannot be read without a line break";
+            return Some(Err(DeserializeError::Message(message.to_string()).into()));
+        }
+
+        let newline = self.encoding.encode_char('\n').unwrap_or(b'\n');
+        let cr = self.encoding.encode_char('\r').unwrap_or(b'\r');
+        self.buf.clear();
+
+        let n = match self.rdr.read_until(newline, &mut self.buf) {
+            Ok(0) => {
+                self.eof = true;
+                return None;
+            }
+            Ok(n) => n,
+            Err(e) => return Some(Err(Error::from(e))),
+        };
+
+        self.index += 1;
+        self.position += n as u64;
+
+        if self.buf.ends_with(&[newline]) {
+            self.buf.pop();
+
+            if self.linebreak == LineBreak::CRLF && self.buf.ends_with(&[cr]) {
+                self.buf.pop();
+            }
+        }
+
+        if self.buf.len() > self.record_width {
+            return Some(Err(DeserializeError::LongRecord(self.buf.len()).into()));
+        }
+
+        self.buf.resize(self.record_width, pad);
+
+        Some(Ok(&self.buf))
+    }
+
     // TODO: use skip_relative once stable
     #[inline]
     fn read_linebreak(&mut self) -> Result<()> {
@@ -323,13 +795,14 @@ where
             return Ok(());
         }
 
-        if let Err(e) = self.rdr.read_exact(&mut self.linebreak_buf) {
+        match self.rdr.read_exact(&mut self.linebreak_buf) {
+            Ok(_) => self.position += self.linebreak_buf.len() as u64,
             // There will not necessarily be a trailing line break, so if reading the linebreak
             // results in an EOF error, mark the reader done and return without error.
-            match e.kind() {
+            Err(e) => match e.kind() {
                 io::ErrorKind::UnexpectedEof => self.eof = true,
                 _ => return Err(Error::from(e)),
-            }
+            },
         }
 
         Ok(())
@@ -391,9 +864,54 @@ where
     type Item = Result<String>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.r
-            .next_record()
-            .map(|record| record.map(|r| String::from_utf8_lossy(r).to_string()))
+        let encoding = self.r.encoding;
+        self.r.next_record().map(|record| {
+            record.map(|r| match encoding.decode(r) {
+                Ok(s) => s.into_owned(),
+                Err(_) => String::from_utf8_lossy(r).into_owned(),
+            })
+        })
+    }
+}
+
+impl<'a, R, T> Iterator for Records<'a, R, T>
+where
+    R: Read,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, position) = (self.r.index, self.r.position);
+        let (types, encoding) = (self.types, self.r.encoding);
+
+        self.r.next_record().map(|record| {
+            record
+                .and_then(|r| types.read_in(r, encoding))
+                .map_err(|e| Error::RecordError(index, position, Box::new(e)))
+        })
+    }
+}
+
+impl<'a, R, T> Iterator for DeserializeRecords<'a, R, T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, position) = (self.r.index, self.r.position);
+        let encoding = self.r.encoding;
+        let fields = &self.fields;
+
+        self.r.next_record().map(|record| {
+            record
+                .and_then(|r| {
+                    let mut de = Deserializer::with_fields(r, fields).encoding(encoding);
+                    Ok(T::deserialize(&mut de)?)
+                })
+                .map_err(|e| Error::RecordError(index, position, Box::new(e)))
+        })
     }
 }
 
@@ -401,9 +919,9 @@ where
 #[allow(dead_code)]
 mod test {
     use super::*;
-    use crate::{FieldSet, FixedWidth};
+    use crate::{FieldSet, FixedWidth, Writer};
     use serde_derive::Deserialize;
-    use std::result;
+    use std::{io::Write, result};
 
     #[test]
     fn read_next_record() {
@@ -529,6 +1047,194 @@ mod test {
         }
     }
 
+    #[test]
+    fn deserialize_records() {
+        let s = "1111222233334444\r\n55556666xxxx8888\r\n9999000011112222";
+
+        let mut rdr = Reader::from_string(s).width(16).linebreak(LineBreak::CRLF);
+        let rows: Vec<Result<Test>> = rdr.deserialize().collect();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].as_ref().unwrap().c, 33334444);
+        assert_eq!(rows[2].as_ref().unwrap().a, "9999");
+
+        match rows[1] {
+            Err(ref e @ Error::RecordError(1, 18, _)) => assert_eq!(
+                e.to_string(),
+                "record 1 at byte 18: field 8..16 at column 9, `xxxx8888`: \
+                 invalid digit found in string"
+            ),
+            _ => panic!("expected an error in the second record"),
+        }
+    }
+
+    #[test]
+    fn validate_records() {
+        let s = "1111222233334444\nxxxx2222333344x4\n1111222233334444\n1111x222333y4444";
+
+        let mut rdr = Reader::from_string(s)
+            .width(16)
+            .linebreak(LineBreak::Newline);
+        let report = rdr.validate::<Test>().unwrap();
+
+        assert!(!report.is_valid());
+        assert_eq!(report.records(), 4);
+        assert_eq!(report.invalid().len(), 2);
+        assert_eq!(report.invalid()[0].index(), 1);
+        assert_eq!(report.invalid()[0].offset(), 17);
+        assert_eq!(report.invalid()[1].index(), 3);
+        assert_eq!(
+            report.to_string(),
+            "2 of 4 records are invalid\n\
+             record 1 at byte 17: field 8..16 at column 9, `333344x4`: \
+             invalid digit found in string\n\
+             record 3 at byte 51: field 8..16 at column 9, `333y4444`: \
+             invalid digit found in string"
+        );
+
+        let mut rdr = Reader::from_string("1111222233334444").width(16);
+        assert!(rdr.validate::<Test>().unwrap().is_valid());
+
+        let mut rdr = Reader::from_string("1111222233334444\nxxxx2222333344x4\n11112222")
+            .width(16)
+            .linebreak(LineBreak::Newline);
+        let report = rdr.validate::<Test>().unwrap();
+
+        assert_eq!(report.records(), 3);
+        assert_eq!(report.invalid().len(), 2);
+        assert_eq!(report.invalid()[1].index(), 2);
+        assert_eq!(report.invalid()[1].offset(), 34);
+        assert!(matches!(
+            report.invalid()[1].errors(),
+            [DeserializeError::PartialRecord(8)]
+        ));
+    }
+
+    #[test]
+    fn read_trailing_partial_record() {
+        let records = |trailing| {
+            let mut rdr = Reader::from_string("foo\nbar\nba")
+                .width(3)
+                .linebreak(LineBreak::Newline)
+                .trailing(trailing);
+            let records = rdr
+                .byte_reader()
+                .map(|r| r.map_err(|e| e.to_string()))
+                .collect::<Vec<_>>();
+
+            (records, rdr.leftover())
+        };
+
+        let (rows, leftover) = records(Trailing::default());
+        assert_eq!(leftover, 2);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[2],
+            Err("data ended with a partial record of 2 bytes".to_string())
+        );
+
+        let (rows, _) = records(Trailing::Short);
+        assert_eq!(rows[2], Ok(b"ba".to_vec()));
+
+        let (rows, _) = records(Trailing::Pad(b' '));
+        assert_eq!(rows[2], Ok(b"ba ".to_vec()));
+
+        let (rows, leftover) = records(Trailing::Ignore);
+        assert_eq!(leftover, 2);
+        assert_eq!(rows, vec![Ok(b"foo".to_vec()), Ok(b"bar".to_vec())]);
+
+        let mut rdr = Reader::from_string("foo\nbar\n")
+            .width(3)
+            .linebreak(LineBreak::Newline);
+        assert_eq!(rdr.byte_reader().filter(|r| r.is_ok()).count(), 2);
+        assert_eq!(rdr.leftover(), 0);
+    }
+
+    #[test]
+    fn read_ragged_lines() {
+        #[derive(Deserialize)]
+        struct Ragged {
+            a: String,
+            b: Option<String>,
+        }
+
+        impl FixedWidth for Ragged {
+            fn fields() -> FieldSet {
+                FieldSet::Seq(vec![FieldSet::new_field(0..4), FieldSet::new_field(4..8)])
+            }
+        }
+
+        let s = "11112222\r\n3333\r\n\r\n555566667\r\n7777 8";
+
+        let mut rdr = Reader::from_string(s)
+            .width(8)
+            .linebreak(LineBreak::CRLF)
+            .ragged(b' ');
+        let rows: Vec<Result<Ragged>> = rdr.deserialize().collect();
+
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0].as_ref().unwrap().b.as_deref(), Some("2222"));
+        assert_eq!(rows[1].as_ref().unwrap().a, "3333");
+        assert!(rows[1].as_ref().unwrap().b.is_none());
+        assert!(rows[2].as_ref().unwrap().b.is_none());
+        assert_eq!(rows[4].as_ref().unwrap().b.as_deref(), Some(" 8"));
+
+        match rows[3] {
+            Err(ref e @ Error::RecordError(3, 18, _)) => assert_eq!(
+                e.to_string(),
+                "record 3 at byte 18: line of 9 bytes is longer than the record width"
+            ),
+            _ => panic!("expected an error in the fourth record"),
+        }
+    }
+
+    #[test]
+    fn read_ragged_encoded_lines() {
+        let mut wrtr = Writer::from_memory()
+            .linebreak(LineBreak::CRLF)
+            .encoding(Encoding::Cp037);
+        for (i, line) in ["AB CD", "EF", "", "GHIJKL"].iter().enumerate() {
+            if i > 0 {
+                wrtr.write_linebreak().unwrap();
+            }
+            wrtr.write_all(&Encoding::Cp037.encode(line).unwrap())
+                .unwrap();
+        }
+        let bytes: Vec<u8> = wrtr.into();
+
+        assert_eq!(bytes[5..7], [0x0D, 0x25]);
+
+        let mut rdr = Reader::from_bytes(bytes)
+            .width(5)
+            .linebreak(LineBreak::CRLF)
+            .encoding(Encoding::Cp037)
+            .ragged(b'@');
+        let rows: Vec<_> = rdr.string_reader().collect();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].as_ref().unwrap(), "AB CD");
+        assert_eq!(rows[1].as_ref().unwrap(), "EF   ");
+        assert_eq!(rows[2].as_ref().unwrap(), "     ");
+        assert!(matches!(
+            rows[3],
+            Err(Error::DeserializeError(DeserializeError::LongRecord(6)))
+        ));
+    }
+
+    #[test]
+    fn ragged_needs_linebreak() {
+        let mut rdr = Reader::from_string("foo\nbar").width(3).ragged(b' ');
+        let rows: Vec<_> = rdr.byte_reader().collect();
+
+        assert_eq!(rows.len(), 1);
+        match rows[0] {
+            Err(Error::DeserializeError(DeserializeError::Message(ref m))) => {
+                assert_eq!(m, "ragged lines cannot be read without a line break")
+            }
+            ref other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_read() {
         let b = "111122223333444411112222333344441111222233334444".as_bytes();
@@ -0,0 +1,261 @@
+use crate::{DeserializeError, Deserializer, Encoding, FieldSet, FixedWidth, Result};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fmt, ops::Range};
+
+type ReadFn<T> = Box<dyn Fn(&[u8], Encoding) -> Result<T>>;
+
+/// The record types of a file that mixes several kinds of records of the same width, such as a
+/// header, details and a trailer. The type of each record is given by a code held in the same
+/// byte range of every record, and each code maps to the fields of its record type.
+///
+/// Records are read into a single type, usually an enum with a variant for each record type.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{FieldSet, FixedWidth, Reader, RecordTypes};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Header {
+///     date: String,
+/// }
+///
+/// impl FixedWidth for Header {
+///     fn fields() -> FieldSet {
+///         FieldSet::Seq(vec![FieldSet::new_field(1..9)])
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Detail {
+///     name: String,
+///     amount: u32,
+/// }
+///
+/// impl FixedWidth for Detail {
+///     fn fields() -> FieldSet {
+///         FieldSet::Seq(vec![FieldSet::new_field(1..5), FieldSet::new_field(5..9)])
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Record {
+///     Header(Header),
+///     Detail(Detail),
+/// }
+///
+/// let types = RecordTypes::new(0..1)
+///     .record("H", Record::Header)
+///     .record("D", Record::Detail);
+///
+/// let mut reader = Reader::from_string("H20240131DAnn 0012DBob 0034").width(9);
+/// let records: Vec<Record> = reader.records(&types).map(Result::unwrap).collect();
+///
+/// assert_eq!(records.len(), 3);
+/// assert_eq!(records[2], Record::Detail(Detail { name: "Bob".to_string(), amount: 34 }));
+///
+/// assert!(types.read(b"X").is_err());
+/// ```
+pub struct RecordTypes<T> {
+    range: Range<usize>,
+    encoding: Option<Encoding>,
+    types: HashMap<String, ReadFn<T>>,
+}
+
+impl<T> RecordTypes<T> {
+    /// Creates a set of record types whose code is held in the given byte range of each record.
+    pub fn new(range: Range<usize>) -> Self {
+        Self {
+            range,
+            encoding: None,
+            types: HashMap::new(),
+        }
+    }
+
+    /// Sets the encoding of the records, used to read their code and their fields. By default,
+    /// records are read in the encoding of the `Reader` they come from, or in `Encoding::Utf8` by
+    /// `read`.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Adds a record type, read with the fields of `R` when the code of a record is `code`, and
+    /// turned into `T` with `f`.
+    pub fn record<R, F>(self, code: &str, f: F) -> Self
+    where
+        R: FixedWidth + DeserializeOwned,
+        F: Fn(R) -> T + 'static,
+    {
+        self.record_with_fields(code, R::fields(), f)
+    }
+
+    /// Adds a record type, read with the given fields when the code of a record is `code`, and
+    /// turned into `T` with `f`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, RecordTypes};
+    /// use std::collections::HashMap;
+    ///
+    /// let trailer = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..1).name("type"),
+    ///     FieldSet::new_field(1..5).name("count"),
+    /// ]);
+    /// let types = RecordTypes::new(0..1)
+    ///     .record_with_fields("T", trailer, |map: HashMap<String, String>| map["count"].clone());
+    ///
+    /// assert_eq!(types.read(b"T0042").unwrap(), "0042");
+    /// ```
+    pub fn record_with_fields<R, F>(mut self, code: &str, fields: FieldSet, f: F) -> Self
+    where
+        R: DeserializeOwned,
+        F: Fn(R) -> T + 'static,
+    {
+        let read = move |bytes: &[u8], encoding: Encoding| {
+            let mut de = Deserializer::with_fields(bytes, &fields).encoding(encoding);
+            Ok(f(R::deserialize(&mut de)?))
+        };
+
+        self.types.insert(code.to_string(), Box::new(read));
+        self
+    }
+
+    /// Reads a record as the record type of its code. Fails with
+    /// `DeserializeError::UnknownRecordType` if no record type has its code.
+    pub fn read(&self, record: &[u8]) -> Result<T> {
+        self.read_in(record, Encoding::default())
+    }
+
+    // Reads a record, in the given encoding unless the record types have their own.
+    pub(crate) fn read_in(&self, record: &[u8], encoding: Encoding) -> Result<T> {
+        let encoding = self.encoding.unwrap_or(encoding);
+        let code = record
+            .get(self.range.clone())
+            .ok_or(DeserializeError::UnexpectedEndOfRecord)?;
+        let code = encoding.decode(code).map_err(DeserializeError::from)?;
+
+        match self.types.get(code.as_ref()) {
+            Some(read) => read(record, encoding),
+            None => Err(DeserializeError::UnknownRecordType(code.into_owned()).into()),
+        }
+    }
+}
+
+impl<T> fmt::Debug for RecordTypes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut codes: Vec<&String> = self.types.keys().collect();
+        codes.sort();
+
+        f.debug_struct("RecordTypes")
+            .field("range", &self.range)
+            .field("encoding", &self.encoding)
+            .field("codes", &codes)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, Reader};
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Header {
+        kind: String,
+        count: usize,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Detail {
+        kind: String,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Record {
+        Header(Header),
+        Detail(Detail),
+    }
+
+    fn types() -> RecordTypes<Record> {
+        RecordTypes::new(0..2)
+            .record_with_fields(
+                "01",
+                FieldSet::Seq(vec![FieldSet::new_field(0..2), FieldSet::new_field(2..6)]),
+                Record::Header,
+            )
+            .record_with_fields(
+                "02",
+                FieldSet::Seq(vec![FieldSet::new_field(0..2), FieldSet::new_field(2..6)]),
+                Record::Detail,
+            )
+    }
+
+    #[test]
+    fn read_record_types() {
+        let mut reader = Reader::from_string("01000202abc 02def ").width(6);
+        let records: Vec<Record> = reader.records(&types()).map(|r| r.unwrap()).collect();
+
+        assert_eq!(
+            records,
+            vec![
+                Record::Header(Header {
+                    kind: "01".to_string(),
+                    count: 2
+                }),
+                Record::Detail(Detail {
+                    kind: "02".to_string(),
+                    name: "abc".to_string()
+                }),
+                Record::Detail(Detail {
+                    kind: "02".to_string(),
+                    name: "def".to_string()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_record_type() {
+        match types().read(b"99abcd") {
+            Err(Error::DeserializeError(DeserializeError::UnknownRecordType(code))) => {
+                assert_eq!(code, "99")
+            }
+            other => panic!("expected UnknownRecordType, got {:?}", other),
+        }
+
+        assert!(types().read(b"0").is_err());
+    }
+
+    #[test]
+    fn record_types_in_encoding() {
+        // "01" and "0005" in EBCDIC code page 037.
+        let record = [0xF0, 0xF1, 0xF0, 0xF0, 0xF0, 0xF5];
+        let encoded = types().encoding(Encoding::Cp037);
+
+        assert_eq!(
+            encoded.read(&record).unwrap(),
+            Record::Header(Header {
+                kind: "01".to_string(),
+                count: 5
+            })
+        );
+
+        let mut reader = Reader::from_bytes(&record[..])
+            .width(6)
+            .encoding(Encoding::Cp037);
+        let records: Vec<Record> = reader.records(&types()).map(|r| r.unwrap()).collect();
+
+        assert_eq!(
+            records,
+            vec![Record::Header(Header {
+                kind: "01".to_string(),
+                count: 5
+            })]
+        );
+    }
+}
@@ -0,0 +1,687 @@
+/*!
+Loading and saving field definitions from JSON, TOML or YAML files.
+
+This module is only available with the `schema` feature enabled:
+
+```toml
+fixed_width = { version = "0.6", features = ["schema"] }
+```
+
+A schema lists the fields of a record, each with either a byte `range` or a `width`. Fields given
+by width start where the previous field ends, just like `FieldSet::builder`. A field with a list of
+`fields` of its own is a group, which becomes a nested `FieldSet::Seq` and sets nothing else. A
+field with a list of `variants` is the tag of a tagged enum, see `FieldSet::variant`, and the
+fields of each variant given by width start where the tag ends.
+
+```rust
+use fixed_width::schema::Schema;
+use std::collections::HashMap;
+
+let schema = Schema::from_toml(r#"
+    width = 15
+    linebreak = "newline"
+
+    [[fields]]
+    name = "name"
+    width = 6
+
+    [[fields]]
+    name = "age"
+    width = 3
+    pad_with = "0"
+    justify = "right"
+
+    [[fields]]
+    name = "height"
+    range = "11..15"
+"#).unwrap();
+
+let fields = schema.field_set().unwrap();
+let mut reader = schema.reader("foobar025  0180\n".as_bytes()).unwrap();
+
+for record in reader.byte_reader() {
+    let bytes = record.unwrap();
+    let h: HashMap<String, String> =
+        fixed_width::from_bytes_with_fields(&bytes, fields.clone()).unwrap();
+
+    assert_eq!(h.get("age").unwrap(), "25");
+}
+```
+*/
+use crate::{
+    error::Error, Booleans, Codec, Encoding, FieldConfig, FieldSet, Justify, LineBreak, Null,
+    Overflow, Reader, Result, Sign, Trim, Variant,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::{error::Error as StdError, fmt, fs, io, ops::Range, path::Path};
+
+/// Errors that occur while loading or saving a schema.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The schema is not valid JSON, or could not be written as JSON.
+    Json(serde_json::Error),
+    /// The schema is not valid TOML.
+    TomlDe(toml::de::Error),
+    /// The schema could not be written as TOML.
+    TomlSer(toml::ser::Error),
+    /// The schema is not valid YAML, or could not be written as YAML.
+    Yaml(serde_yaml::Error),
+    /// A field range is not of the form `start..end`.
+    InvalidRange(String),
+    /// A field has neither a range nor a width.
+    MissingRange(String),
+    /// A field has both a range and a width.
+    RangeAndWidth(String),
+    /// The format of a schema file could not be determined from its extension.
+    UnknownFormat(String),
+    /// A field has a date format, but the `chrono` feature is not enabled.
+    DateFormat(String),
+    /// A group of fields sets an option besides its `fields`, which would be ignored.
+    GroupOptions(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::Json(ref e) => write!(f, "{}", e),
+            SchemaError::TomlDe(ref e) => write!(f, "{}", e),
+            SchemaError::TomlSer(ref e) => write!(f, "{}", e),
+            SchemaError::Yaml(ref e) => write!(f, "{}", e),
+            SchemaError::InvalidRange(ref r) => write!(f, "invalid range `{}`", r),
+            SchemaError::MissingRange(ref name) => {
+                write!(f, "must supply a range or width for field: {}", name)
+            }
+            SchemaError::RangeAndWidth(ref name) => {
+                write!(f, "specify either a range or a width for field: {}", name)
+            }
+            SchemaError::UnknownFormat(ref path) => {
+                write!(f, "unknown schema format for file: {}", path)
+            }
+            SchemaError::GroupOptions(ref name) => {
+                write!(f, "group {} can only have fields", name)
+            }
+            SchemaError::DateFormat(ref name) => write!(
+                f,
+                "the date format of field {} needs the `chrono` feature",
+                name
+            ),
+        }
+    }
+}
+
+impl StdError for SchemaError {
+    fn cause(&self) -> Option<&dyn StdError> {
+        match self {
+            SchemaError::Json(ref e) => Some(e),
+            SchemaError::TomlDe(ref e) => Some(e),
+            SchemaError::TomlSer(ref e) => Some(e),
+            SchemaError::Yaml(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A record layout that can be read from and written to a file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    /// The width in bytes of each record. Defaults to the end of the last field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+    /// The line break between records. Defaults to `LineBreak::None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linebreak: Option<LineBreak>,
+    /// The encoding of the text in each record. Defaults to `Encoding::Utf8`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// The fields of the record.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// The definition of a single field, or of a group of fields, in a `Schema`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    /// Name of the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Byte range of the field, written as `"start..end"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+    /// Width in bytes of the field. The field starts where the previous one ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+    /// The character to use for padding the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pad_with: Option<char>,
+    /// The justification of the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justify: Option<Justify>,
+    /// How the padding of the field is removed when it is read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim: Option<Trim>,
+    /// What to do with a value that is longer than the field, such as `"truncate_left"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<Overflow>,
+    /// How the value of the field is represented, such as `{ type = "packed" }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<Codec>,
+    /// The number of digits after the implied decimal point of a numeric field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<usize>,
+    /// Where the sign of a signed number is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sign: Option<Sign>,
+    /// How the values of a boolean field are written and read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub booleans: Option<Booleans>,
+    /// How a missing value is represented in the field, such as `{ fill = "0" }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub null: Option<Null>,
+    /// The date format of the field. Loading a schema with a date format fails unless the
+    /// `chrono` feature is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The fields of a group. A group has no range, width or other option of its own, and
+    /// loading fails if it sets one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldSchema>>,
+    /// The variants of a tagged enum whose tag is held in this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<VariantSchema>>,
+}
+
+/// The definition of a variant of a tagged enum in a `Schema`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VariantSchema {
+    /// The value of the tag field that selects the variant.
+    pub tag: String,
+    /// The name of the variant.
+    pub name: String,
+    /// The fields of the variant.
+    #[serde(default)]
+    pub fields: Vec<FieldSchema>,
+}
+
+impl Schema {
+    /// Reads a schema from a JSON string.
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(|e| SchemaError::Json(e).into())
+    }
+
+    /// Reads a schema from a TOML string.
+    pub fn from_toml(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| SchemaError::TomlDe(e).into())
+    }
+
+    /// Reads a schema from a YAML string.
+    pub fn from_yaml(s: &str) -> Result<Self> {
+        serde_yaml::from_str(s).map_err(|e| SchemaError::Yaml(e).into())
+    }
+
+    /// Writes the schema as a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| SchemaError::Json(e).into())
+    }
+
+    /// Writes the schema as a TOML string.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| SchemaError::TomlSer(e).into())
+    }
+
+    /// Writes the schema as a YAML string.
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).map_err(|e| SchemaError::Yaml(e).into())
+    }
+
+    /// Reads a schema from a file. The format is chosen from the file extension, which must be
+    /// one of `json`, `toml`, `yaml` or `yml`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let ext = extension(path)?;
+        let s = fs::read_to_string(path)?;
+
+        match ext.as_str() {
+            "json" => Self::from_json(&s),
+            "toml" => Self::from_toml(&s),
+            _ => Self::from_yaml(&s),
+        }
+    }
+
+    /// Writes the schema to a file. The format is chosen from the file extension, which must be
+    /// one of `json`, `toml`, `yaml` or `yml`.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let s = match extension(path)?.as_str() {
+            "json" => self.to_json()?,
+            "toml" => self.to_toml()?,
+            _ => self.to_yaml()?,
+        };
+
+        fs::write(path, s)?;
+        Ok(())
+    }
+
+    /// Creates a schema describing the given fields. Every field is written with its byte range.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, schema::Schema};
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..4).name("foo"),
+    ///     FieldSet::new_field(4..8).name("bar").pad_with('0'),
+    /// ]);
+    ///
+    /// let schema = Schema::from_field_set(&fields);
+    /// assert_eq!(schema.fields[1].range.as_deref(), Some("4..8"));
+    /// assert_eq!(schema.fields[1].pad_with, Some('0'));
+    /// ```
+    pub fn from_field_set(fields: &FieldSet) -> Self {
+        Self {
+            width: None,
+            linebreak: None,
+            encoding: None,
+            fields: fields.clone().into_iter().map(FieldSchema::from).collect(),
+        }
+    }
+
+    /// Builds the `FieldSet` described by the schema. Fails if a field has an invalid range, or if
+    /// the resulting layout is invalid according to `FieldSet::validate`.
+    pub fn field_set(&self) -> Result<FieldSet> {
+        let mut offset = 0;
+        let fields = build_fields(&self.fields, &mut offset)?;
+        fields.validate().into_result()?;
+        Ok(fields)
+    }
+
+    /// The width in bytes of each record: the `width` given in the schema, or the end of the last
+    /// field if there is none.
+    pub fn record_width(&self) -> Result<usize> {
+        match self.width {
+            Some(width) => Ok(width),
+            None => Ok(self.field_set()?.validate().width()),
+        }
+    }
+
+    /// Creates a `Reader` for the given data, configured with the record width, line break and
+    /// encoding of the schema.
+    pub fn reader<R: io::Read>(&self, rdr: R) -> Result<Reader<R>> {
+        Ok(Reader::from_reader(rdr)
+            .width(self.record_width()?)
+            .linebreak(self.linebreak.clone().unwrap_or(LineBreak::None))
+            .encoding(self.encoding.unwrap_or_default()))
+    }
+}
+
+impl From<FieldSet> for FieldSchema {
+    fn from(fields: FieldSet) -> Self {
+        match fields {
+            FieldSet::Item(conf) => Self::from(conf),
+            FieldSet::Seq(seq) => Self {
+                fields: Some(seq.into_iter().map(Self::from).collect()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<FieldConfig> for FieldSchema {
+    fn from(conf: FieldConfig) -> Self {
+        let default = FieldConfig::default();
+
+        Self {
+            name: conf.name,
+            range: Some(format!("{}..{}", conf.range.start, conf.range.end)),
+            width: None,
+            pad_with: Some(conf.pad_with).filter(|c| *c != default.pad_with),
+            justify: Some(conf.justify).filter(|j| *j != default.justify),
+            trim: Some(conf.trim).filter(|t| *t != default.trim),
+            overflow: conf.overflow,
+            codec: Some(conf.codec).filter(|c| *c != default.codec),
+            scale: Some(conf.scale).filter(|s| *s != default.scale),
+            sign: Some(conf.sign).filter(|s| *s != default.sign),
+            booleans: conf.booleans,
+            null: conf.null,
+            #[cfg(feature = "chrono")]
+            format: conf.format,
+            #[cfg(not(feature = "chrono"))]
+            format: None,
+            fields: None,
+            variants: Some(conf.variants)
+                .filter(|v| !v.is_empty())
+                .map(|variants| variants.into_iter().map(VariantSchema::from).collect()),
+        }
+    }
+}
+
+impl From<Variant> for VariantSchema {
+    fn from(variant: Variant) -> Self {
+        let fields = match variant.fields {
+            FieldSet::Seq(seq) => seq.into_iter().map(FieldSchema::from).collect(),
+            item => vec![FieldSchema::from(item)],
+        };
+
+        Self {
+            tag: variant.tag,
+            name: variant.name,
+            fields,
+        }
+    }
+}
+
+fn build_fields(fields: &[FieldSchema], offset: &mut usize) -> Result<FieldSet> {
+    let mut seq = vec![];
+
+    for field in fields {
+        seq.push(build_field(field, offset)?);
+    }
+
+    Ok(FieldSet::Seq(seq))
+}
+
+fn build_field(field: &FieldSchema, offset: &mut usize) -> Result<FieldSet> {
+    if let Some(ref fields) = field.fields {
+        let options = FieldSchema {
+            fields: None,
+            ..field.clone()
+        };
+        if options != FieldSchema::default() {
+            let label = field.name.clone().unwrap_or_else(|| format!("#{}", offset));
+            return Err(SchemaError::GroupOptions(label).into());
+        }
+
+        return build_fields(fields, offset);
+    }
+
+    let label = || field.name.clone().unwrap_or_else(|| format!("#{}", offset));
+
+    #[cfg(not(feature = "chrono"))]
+    if field.format.is_some() {
+        return Err(SchemaError::DateFormat(label()).into());
+    }
+
+    let range = match (&field.range, field.width) {
+        (Some(_), Some(_)) => return Err(SchemaError::RangeAndWidth(label()).into()),
+        (Some(r), None) => parse_range(r)?,
+        (None, Some(w)) => *offset..*offset + w,
+        (None, None) => return Err(SchemaError::MissingRange(label()).into()),
+    };
+
+    *offset = range.end;
+
+    let mut set = FieldSet::new_field(range);
+    if let Some(ref name) = field.name {
+        set = set.name(name.clone());
+    }
+    if let Some(pad_with) = field.pad_with {
+        set = set.pad_with(pad_with);
+    }
+    if let Some(justify) = field.justify {
+        set = set.justify(justify);
+    }
+    if let Some(trim) = field.trim {
+        set = set.trim(trim);
+    }
+    if let Some(overflow) = field.overflow {
+        set = set.overflow(overflow);
+    }
+    if let Some(codec) = field.codec {
+        set = set.codec(codec);
+    }
+    if let Some(scale) = field.scale {
+        set = set.scale(scale);
+    }
+    if let Some(sign) = field.sign {
+        set = set.sign(sign);
+    }
+    if let Some(ref booleans) = field.booleans {
+        set = set.booleans(booleans.clone());
+    }
+    if let Some(ref null) = field.null {
+        set = set.null(null.clone());
+    }
+    #[cfg(feature = "chrono")]
+    if let Some(ref format) = field.format {
+        set = set.format(format.clone());
+    }
+    for variant in field.variants.iter().flatten() {
+        let fields = build_fields(&variant.fields, &mut offset.clone())?;
+        set = set.variant(variant.tag.clone(), variant.name.clone(), fields);
+    }
+
+    Ok(set)
+}
+
+fn parse_range(s: &str) -> Result<Range<usize>> {
+    let parts: Vec<&str> = s.split("..").collect();
+
+    match parts.as_slice() {
+        [start, end] => match (start.trim().parse(), end.trim().parse()) {
+            (Ok(start), Ok(end)) => Ok(start..end),
+            _ => Err(SchemaError::InvalidRange(s.to_string()).into()),
+        },
+        _ => Err(SchemaError::InvalidRange(s.to_string()).into()),
+    }
+}
+
+fn extension(path: &Path) -> Result<String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "json" | "toml" | "yaml" | "yml" => Ok(ext),
+        _ => Err(Error::from(SchemaError::UnknownFormat(
+            path.display().to_string(),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const JSON: &str = r#"{
+        "fields": [
+            { "name": "id", "width": 3 },
+            { "fields": [
+                { "name": "x", "width": 2, "pad_with": "0", "justify": "right" },
+                { "name": "y", "width": 2 }
+            ] },
+            { "name": "name", "range": "8..12" }
+        ]
+    }"#;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: usize,
+        y: usize,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Record {
+        id: usize,
+        point: Point,
+        name: String,
+    }
+
+    #[test]
+    fn field_set_from_json() {
+        let schema = Schema::from_json(JSON).unwrap();
+        let fields = schema.field_set().unwrap();
+
+        let ranges: Vec<_> = fields
+            .clone()
+            .flatten()
+            .into_iter()
+            .map(|f| f.range)
+            .collect();
+        assert_eq!(ranges, vec![0..3, 3..5, 5..7, 8..12]);
+        assert_eq!(schema.record_width().unwrap(), 12);
+
+        let record: Record = crate::from_str_with_fields("12301 2 bob ", fields).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                id: 123,
+                point: Point { x: 1, y: 2 },
+                name: "bob".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn same_schema_in_every_format() {
+        let json = Schema::from_json(JSON).unwrap();
+        let toml = Schema::from_toml(&json.to_toml().unwrap()).unwrap();
+        let yaml = Schema::from_yaml(&json.to_yaml().unwrap()).unwrap();
+
+        assert_eq!(json, toml);
+        assert_eq!(json, yaml);
+        assert_eq!(json, Schema::from_json(&json.to_json().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn round_trip_field_set() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).name("a").justify(Justify::Right),
+            FieldSet::Seq(vec![FieldSet::new_field(4..6).pad_with('x')]),
+            FieldSet::new_field(6..8).codec(Codec::Binary {
+                endian: crate::Endian::Little,
+                signed: false,
+            }),
+            FieldSet::new_field(8..12).scale(2).sign(Sign::Trailing),
+            FieldSet::new_field(12..13).booleans(Booleans::yes_no().blank(false)),
+            FieldSet::new_field(13..15).null(Null::Byte(0)),
+            FieldSet::new_field(15..17)
+                .trim(Trim::Raw)
+                .overflow(Overflow::TruncateLeft),
+        ]);
+
+        let schema = Schema::from_field_set(&fields);
+        let yaml = schema.to_yaml().unwrap();
+        let loaded = Schema::from_yaml(&yaml).unwrap().field_set().unwrap();
+
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", fields));
+    }
+
+    #[test]
+    fn tagged_enum_schema() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Entry {
+            Header(String),
+            Detail { name: String, amount: u32 },
+        }
+
+        let fields = FieldSet::new_field(0..1)
+            .variant(
+                "H",
+                "Header",
+                FieldSet::Seq(vec![FieldSet::new_field(1..9)]),
+            )
+            .variant(
+                "D",
+                "Detail",
+                FieldSet::Seq(vec![
+                    FieldSet::new_field(1..5),
+                    FieldSet::new_field(5..9).codec(Codec::Overpunch),
+                ]),
+            );
+
+        let schema = Schema::from_field_set(&FieldSet::Seq(vec![fields.clone()]));
+        let toml = Schema::from_toml(&schema.to_toml().unwrap()).unwrap();
+        assert_eq!(toml, schema);
+
+        let loaded = toml.field_set().unwrap();
+        assert_eq!(
+            format!("{:?}", loaded),
+            format!("{:?}", FieldSet::Seq(vec![fields]))
+        );
+
+        let schema = Schema::from_json(
+            r#"{ "fields": [{ "width": 1, "variants": [
+                { "tag": "H", "name": "Header", "fields": [{ "width": 8 }] },
+                { "tag": "D", "name": "Detail", "fields": [{ "width": 4 }, { "width": 4 }] }
+            ] }] }"#,
+        )
+        .unwrap();
+        assert_eq!(schema.record_width().unwrap(), 9);
+
+        let entry: Entry =
+            crate::from_str_with_fields("DAnn 0012", schema.field_set().unwrap()).unwrap();
+        assert_eq!(
+            entry,
+            Entry::Detail {
+                name: "Ann".to_string(),
+                amount: 12
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_schemas() {
+        let missing = Schema::from_json(r#"{ "fields": [{ "name": "a" }] }"#).unwrap();
+        match missing.field_set() {
+            Err(Error::SchemaError(SchemaError::MissingRange(name))) => assert_eq!(name, "a"),
+            r => panic!("expected a missing range error, got {:?}", r),
+        }
+
+        let both = Schema::from_json(r#"{ "fields": [{ "range": "0..2", "width": 2 }] }"#);
+        match both.unwrap().field_set() {
+            Err(Error::SchemaError(SchemaError::RangeAndWidth(_))) => {}
+            r => panic!("expected a range and width error, got {:?}", r),
+        }
+
+        let bad_range = Schema::from_json(r#"{ "fields": [{ "range": "0-2" }] }"#);
+        match bad_range.unwrap().field_set() {
+            Err(Error::SchemaError(SchemaError::InvalidRange(r))) => assert_eq!(r, "0-2"),
+            r => panic!("expected an invalid range error, got {:?}", r),
+        }
+
+        let group = Schema::from_json(
+            r#"{"fields": [{"name": "address", "range": "10..40", "fields": [{"width": 4}]}]}"#,
+        );
+        match group.unwrap().field_set() {
+            Err(Error::SchemaError(SchemaError::GroupOptions(name))) => assert_eq!(name, "address"),
+            r => panic!("expected a group options error, got {:?}", r),
+        }
+
+        let overlap = Schema::from_json(r#"{ "fields": [{ "width": 4 }, { "range": "2..6" }] }"#);
+        match overlap.unwrap().field_set() {
+            Err(Error::LayoutError(_)) => {}
+            r => panic!("expected a layout error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn date_formats() {
+        let schema =
+            Schema::from_json(r#"{ "fields": [{ "name": "d", "width": 8, "format": "%Y%m%d" }] }"#)
+                .unwrap();
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            format!("{:?}", schema.field_set().unwrap()),
+            format!(
+                "{:?}",
+                FieldSet::Seq(vec![FieldSet::new_field(0..8).name("d").format("%Y%m%d")])
+            )
+        );
+
+        #[cfg(not(feature = "chrono"))]
+        match schema.field_set() {
+            Err(Error::SchemaError(SchemaError::DateFormat(name))) => assert_eq!(name, "d"),
+            r => panic!("expected a date format error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn unknown_file_format() {
+        match Schema::from_file("layout.txt") {
+            Err(Error::SchemaError(SchemaError::UnknownFormat(_))) => {}
+            r => panic!("expected an unknown format error, got {:?}", r),
+        }
+    }
+}
@@ -1,6 +1,15 @@
-use crate::{error::Error, writer::Writer, FieldConfig, FieldSet, FixedWidth, Justify, Result};
+#[cfg(feature = "chrono")]
+use crate::date;
+use crate::{
+    error::Error, numeric, writer::Writer, Codec, Encoding, FieldConfig, FieldSet, FixedWidth,
+    Justify, Null, Overflow, Result, Sign,
+};
 use serde::ser::{self, Error as SerError, Serialize};
-use std::{error::Error as StdError, fmt, io, iter, vec};
+use std::{borrow::Cow, error::Error as StdError, fmt, io, iter, mem, vec};
+
+// The name of a newtype struct holding the text of a number, such as a decimal, that is written
+// the way integers are rather than as text.
+pub(crate) const NUMBER: &str = "$fixed_width::Number";
 
 /// Serializes the given type that implements `FixedWidth` and `Serialize` to a `String`.
 ///
@@ -154,6 +163,24 @@ pub enum SerializeError {
     Unsupported(String),
     /// The number of `Field`s given were less than the number of values to be serialized.
     UnexpectedEndOfFields,
+    /// A character can not be represented in the encoding of the output.
+    Unencodable(char, Encoding),
+    /// A value written to a packed decimal or binary field is not a valid number for the field.
+    InvalidNumber(String),
+    /// A value is too large for its field.
+    Overflow(String),
+    /// A value has more decimals than the scale of its field, and would have to be rounded.
+    Inexact(String, usize),
+    /// A value written to a date field is not a date or time that can be written in its format.
+    InvalidDate(String),
+    /// A value is longer than the width of its field, given as the name of the field, the value
+    /// and the width, and the overflow policy of the field is `Overflow::Error`.
+    FieldOverflow(String, String, usize),
+    /// The encoded pad character of a field does not evenly fill the bytes left in the field,
+    /// given as the name of the field, the pad character and the number of bytes left.
+    UnevenPad(String, char, usize),
+    /// A variant of a tagged enum has no tag value in the fields, see `FieldSet::variant`.
+    UnknownVariant(String),
 }
 
 impl fmt::Display for SerializeError {
@@ -162,6 +189,28 @@ impl fmt::Display for SerializeError {
             SerializeError::Message(ref e) => write!(f, "{}", e),
             SerializeError::Unsupported(ref e) => write!(f, "{}", e),
             SerializeError::UnexpectedEndOfFields => write!(f, "Unexpected End of Fields"),
+            SerializeError::Unencodable(c, encoding) => {
+                write!(f, "character {:?} can not be encoded as {}", c, encoding)
+            }
+            SerializeError::InvalidNumber(ref e) => write!(f, "`{}` is not a valid number", e),
+            SerializeError::Overflow(ref e) => write!(f, "`{}` does not fit in its field", e),
+            SerializeError::Inexact(ref e, scale) => {
+                write!(f, "`{}` can not be written with {} decimals", e, scale)
+            }
+            SerializeError::InvalidDate(ref e) => write!(f, "{}", e),
+            SerializeError::FieldOverflow(ref name, ref val, width) => write!(
+                f,
+                "`{}` is longer than the {} bytes of field `{}`",
+                val, width, name
+            ),
+            SerializeError::UnevenPad(ref name, c, remaining) => write!(
+                f,
+                "pad character {:?} does not evenly fill the {} bytes left in field `{}`",
+                c, remaining, name
+            ),
+            SerializeError::UnknownVariant(ref e) => {
+                write!(f, "variant `{}` has no tag value in the fields", e)
+            }
         }
     }
 }
@@ -183,6 +232,9 @@ impl SerError for Error {
 pub struct Serializer<'w, W: 'w + io::Write> {
     fields: iter::Peekable<vec::IntoIter<FieldConfig>>,
     wrtr: &'w mut W,
+    encoding: Encoding,
+    overflow: Option<Overflow>,
+    number: bool,
 }
 
 impl<'w, W: 'w + io::Write> Serializer<'w, W> {
@@ -215,9 +267,71 @@ impl<'w, W: 'w + io::Write> Serializer<'w, W> {
         Self {
             fields: fields.flatten().into_iter().peekable(),
             wrtr,
+            encoding: Encoding::default(),
+            overflow: None,
+            number: false,
         }
     }
 
+    /// Sets the encoding to write text in. Defaults to `Encoding::Utf8`. Values are padded to
+    /// the byte width of their field after encoding.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Encoding, FieldSet, Serializer, Writer};
+    /// use serde::Serialize;
+    ///
+    /// let fields = FieldSet::Seq(vec![
+    ///     FieldSet::new_field(0..5),
+    ///     FieldSet::new_field(5..7),
+    /// ]);
+    ///
+    /// let mut writer = Writer::from_memory();
+    ///
+    /// {
+    ///     let mut ser = Serializer::new(&mut writer, fields).encoding(Encoding::Cp037);
+    ///     ("CAFÉ", 42).serialize(&mut ser).unwrap();
+    /// }
+    ///
+    /// let bytes: Vec<u8> = writer.into();
+    /// assert_eq!(bytes, vec![0xC3, 0xC1, 0xC6, 0x71, 0x40, 0xF4, 0xF2]);
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets what to do with a value that is longer than its field, for fields that do not set
+    /// their own policy. By default, numbers fail with `SerializeError::FieldOverflow` and any
+    /// other value is truncated on the right.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{FieldSet, Overflow, Serializer, Writer};
+    /// use serde::Serialize;
+    ///
+    /// let fields = FieldSet::Seq(vec![FieldSet::new_field(0..4), FieldSet::new_field(4..7)]);
+    /// let mut writer = Writer::from_memory();
+    ///
+    /// {
+    ///     let mut ser = Serializer::new(&mut writer, fields.clone());
+    ///     assert!(("abcdef", 12345).serialize(&mut ser).is_err());
+    /// }
+    ///
+    /// let mut writer = Writer::from_memory();
+    ///
+    /// {
+    ///     let mut ser = Serializer::new(&mut writer, fields).overflow(Overflow::Error);
+    ///     assert!(("abcdef", 123).serialize(&mut ser).is_err());
+    /// }
+    /// ```
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
     fn next_field(&mut self) -> Result<FieldConfig> {
         match self.fields.next() {
             Some(f) => Ok(f),
@@ -225,6 +339,155 @@ impl<'w, W: 'w + io::Write> Serializer<'w, W> {
         }
     }
 
+    fn pad_bytes(&self, field: &FieldConfig) -> Result<Vec<u8>> {
+        let mut buf = [0; 4];
+        let pad = field.pad_with.encode_utf8(&mut buf);
+        Ok(self.encoding.encode(pad)?.into_owned())
+    }
+
+    // Cuts a value that is longer than its field according to the overflow policy of the field.
+    fn fit<'b>(
+        &self,
+        field: &FieldConfig,
+        bytes: &'b [u8],
+        val: &str,
+        numeric: bool,
+    ) -> Result<Cow<'b, [u8]>> {
+        let width = field.width();
+        if bytes.len() <= width {
+            return Ok(Cow::Borrowed(bytes));
+        }
+
+        let policy = field.overflow.or(self.overflow).unwrap_or(match numeric {
+            true => Overflow::Error,
+            false => Overflow::TruncateRight,
+        });
+
+        match policy {
+            Overflow::Error => {
+                Err(SerializeError::FieldOverflow(field.label(), val.to_string(), width).into())
+            }
+            Overflow::TruncateRight => Ok(Cow::Borrowed(&bytes[..self.end_before(bytes, width)])),
+            Overflow::TruncateLeft => {
+                let mut start = bytes.len() - width;
+                while !self.encoding.is_char_boundary(bytes, start) {
+                    start += 1;
+                }
+                Ok(Cow::Borrowed(&bytes[start..]))
+            }
+            Overflow::Ellipsis => {
+                let marker = self.encoding.encode("...")?;
+                let keep = width.saturating_sub(marker.len());
+                let mut v = bytes[..self.end_before(bytes, keep)].to_vec();
+                v.extend_from_slice(&marker[..width - keep]);
+                Ok(Cow::Owned(v))
+            }
+        }
+    }
+
+    // The last character boundary of the encoded bytes at or before `end`, so that a value is
+    // never cut in the middle of a character. The bytes cut off are padded.
+    fn end_before(&self, bytes: &[u8], mut end: usize) -> usize {
+        while !self.encoding.is_char_boundary(bytes, end) {
+            end -= 1;
+        }
+        end
+    }
+
+    fn write_str(&mut self, val: &str, numeric: bool) -> Result<()> {
+        #[cfg(feature = "chrono")]
+        let formatted;
+        #[cfg(feature = "chrono")]
+        let val = match self.fields.peek().and_then(|f| f.format.as_deref()) {
+            Some(format) => {
+                formatted = date::format(format, val)?;
+                formatted.as_str()
+            }
+            None => val,
+        };
+
+        let scaled;
+        let val = match self.fields.peek().map(|f| f.scale) {
+            Some(scale) if scale > 0 => {
+                scaled = numeric::unscale(val, scale)?;
+                scaled.as_str()
+            }
+            _ => val,
+        };
+
+        match self.fields.peek().map(|f| f.codec) {
+            None | Some(Codec::Text) => {}
+            Some(Codec::Overpunch) => {
+                let field = self.next_field()?;
+                let text = numeric::punch(val, field.width(), field.sign == Sign::Leading)?;
+                let bytes = self.encoding.encode(&text)?;
+                return self.write_bytes(&bytes);
+            }
+            Some(codec) => {
+                let field = self.next_field()?;
+                let bytes = numeric::encode(&codec, val, field.width(), self.encoding)?;
+                return self.write_bytes(&bytes);
+            }
+        }
+
+        let sign = self.fields.peek().map_or(Sign::Inline, |f| f.sign);
+        if let Some((before, unsigned, after)) = numeric::place_sign(val, sign) {
+            let field = self.next_field()?;
+            let signs = before.len() + after.len();
+            // A sign that does not fit can not be truncated, whatever the overflow policy.
+            if signs > field.width() {
+                let width = field.width();
+                return Err(
+                    SerializeError::FieldOverflow(field.label(), val.to_string(), width).into(),
+                );
+            }
+
+            let mut inner = field.clone();
+            inner.range.end = field.range.end - signs;
+
+            let mut bytes = self.encoding.encode(before)?.into_owned();
+            let unsigned = self.encoding.encode(unsigned)?;
+            let unsigned = self.fit(&inner, &unsigned, val, true)?;
+            bytes.extend(pad(&unsigned, &inner, &self.pad_bytes(&field)?)?);
+            bytes.extend_from_slice(&self.encoding.encode(after)?);
+            return self.write_bytes(&bytes);
+        }
+
+        // Numbers, and the text of decimals written to scaled fields, are numeric.
+        let numeric = numeric || self.fields.peek().is_some_and(|f| f.scale > 0);
+        let bytes = self.encoding.encode(val)?;
+        let field = self.next_field()?;
+        let bytes = self.fit(&field, &bytes, val, numeric)?;
+        let bytes = pad(&bytes, &field, &self.pad_bytes(&field)?)?;
+        self.write_bytes(&bytes)
+    }
+
+    // Writes the tag value of a variant of a tagged enum, followed by the fields of the variant.
+    // Returns false if the enum is not tagged.
+    fn write_tag(&mut self, variant: &str) -> Result<bool> {
+        let tagged = match self.fields.peek() {
+            Some(field) if !field.variants.is_empty() => field
+                .variants
+                .iter()
+                .find(|v| v.name == variant)
+                .cloned()
+                .ok_or_else(|| SerializeError::UnknownVariant(variant.to_string()))?,
+            _ => return Ok(false),
+        };
+
+        self.write_str(&tagged.tag, false)?;
+
+        let fields: Vec<FieldConfig> = tagged
+            .fields
+            .flatten()
+            .into_iter()
+            .chain(self.fields.by_ref())
+            .collect();
+        self.fields = fields.into_iter().peekable();
+
+        Ok(true)
+    }
+
     fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
         self.wrtr.write_all(bytes)?;
         Ok(())
@@ -234,7 +497,7 @@ impl<'w, W: 'w + io::Write> Serializer<'w, W> {
 macro_rules! serialize_with_str {
     ($ser_fn:ident, $int_ty:ty) => {
         fn $ser_fn(self, val: $int_ty) -> Result<Self::Ok> {
-            self.serialize_str(&val.to_string())
+            self.write_str(&val.to_string(), true)
         }
     };
 }
@@ -258,26 +521,61 @@ impl<'a, 'w, W: io::Write> ser::Serializer for &'a mut Serializer<'w, W> {
     serialize_with_str!(serialize_i32, i32);
     serialize_with_str!(serialize_u64, u64);
     serialize_with_str!(serialize_i64, i64);
+    serialize_with_str!(serialize_u128, u128);
+    serialize_with_str!(serialize_i128, i128);
     serialize_with_str!(serialize_f32, f32);
     serialize_with_str!(serialize_f64, f64);
-    serialize_with_str!(serialize_char, char);
+
+    fn serialize_char(self, val: char) -> Result<Self::Ok> {
+        self.serialize_str(&val.to_string())
+    }
 
     fn serialize_bool(self, val: bool) -> Result<Self::Ok> {
-        self.serialize_str(&(val as u8).to_string())
+        match self.fields.peek().and_then(|f| f.booleans.clone()) {
+            Some(booleans) => self.serialize_str(booleans.write(val)),
+            None => self.serialize_str(&(val as u8).to_string()),
+        }
     }
 
     fn serialize_str(self, val: &str) -> Result<Self::Ok> {
-        let bytes = val.as_bytes();
-        self.serialize_bytes(bytes)
+        let numeric = mem::take(&mut self.number);
+        self.write_str(val, numeric)
     }
 
     fn serialize_bytes(self, val: &[u8]) -> Result<Self::Ok> {
-        let bytes = pad(val, &self.next_field()?);
+        let field = self.next_field()?;
+        let bytes = self.fit(&field, val, &String::from_utf8_lossy(val), false)?;
+        let bytes = pad(&bytes, &field, &self.pad_bytes(&field)?)?;
         self.write_bytes(&bytes)
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
-        self.serialize_bytes(&[])
+        match self.fields.peek().and_then(|f| f.null.clone()) {
+            Some(Null::Text(text)) => {
+                let field = self.next_field()?;
+                let bytes = self.encoding.encode(&text)?;
+                // A null text that does not fit is a mistake in the layout, never truncate it.
+                if bytes.len() > field.width() {
+                    let (label, width) = (field.label(), field.width());
+                    return Err(SerializeError::FieldOverflow(label, text, width).into());
+                }
+                self.write_bytes(&pad(&bytes, &field, &self.pad_bytes(&field)?)?)
+            }
+            Some(Null::Fill(c)) => {
+                // The fill character pads the whole field, and is the one reported if it does not
+                // fill it evenly.
+                let mut field = self.next_field()?;
+                field.pad_with = c;
+                let mut buf = [0; 4];
+                let fill = self.encoding.encode(c.encode_utf8(&mut buf))?;
+                self.write_bytes(&pad(&[], &field, &fill)?)
+            }
+            Some(Null::Byte(b)) => {
+                let field = self.next_field()?;
+                self.write_bytes(&vec![b; field.width()])
+            }
+            None => self.serialize_bytes(&[]),
+        }
     }
 
     fn serialize_some<T: ?Sized + Serialize>(self, val: &T) -> Result<Self::Ok> {
@@ -298,24 +596,31 @@ impl<'a, 'w, W: io::Write> ser::Serializer for &'a mut Serializer<'w, W> {
         _variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok> {
-        self.serialize_str(variant)
+        match self.write_tag(variant)? {
+            true => Ok(()),
+            false => self.serialize_str(variant),
+        }
     }
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         val: &T,
     ) -> Result<Self::Ok> {
-        val.serialize(self)
+        self.number = name == NUMBER;
+        let result = val.serialize(&mut *self);
+        self.number = false;
+        result
     }
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         val: &T,
     ) -> Result<Self::Ok> {
+        self.write_tag(variant)?;
         val.serialize(&mut *self)
     }
 
@@ -342,7 +647,9 @@ impl<'a, 'w, W: io::Write> ser::Serializer for &'a mut Serializer<'w, W> {
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        variant.serialize(&mut *self)?;
+        if !self.write_tag(variant)? {
+            variant.serialize(&mut *self)?;
+        }
         Ok(self)
     }
 
@@ -361,7 +668,9 @@ impl<'a, 'w, W: io::Write> ser::Serializer for &'a mut Serializer<'w, W> {
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        variant.serialize(&mut *self)?;
+        if !self.write_tag(variant)? {
+            variant.serialize(&mut *self)?;
+        }
         Ok(self)
     }
 }
@@ -469,24 +778,30 @@ impl<'a, 'w, W: io::Write> ser::SerializeStructVariant for &'a mut Serializer<'w
     }
 }
 
+/// Pads the bytes of a value to the width of its field with the encoded pad character, which may
+/// be more than one byte long.
 #[inline]
-fn pad(bytes: &[u8], field: &FieldConfig) -> Vec<u8> {
+fn pad(bytes: &[u8], field: &FieldConfig, pad: &[u8]) -> Result<Vec<u8>> {
     let width = field.width();
-    let pad = field.pad_with as u8;
     let mut v = bytes.to_vec();
 
-    if v.len() > width {
-        v.resize(width, pad);
-    } else {
-        for _ in 0..(width - v.len()) {
-            match field.justify {
-                Justify::Left => v.push(pad),
-                _ => v.insert(0, pad),
-            }
-        }
+    if v.len() >= width {
+        v.truncate(width);
+        return Ok(v);
+    }
+
+    let remaining = width - v.len();
+    if pad.is_empty() || remaining % pad.len() != 0 {
+        return Err(SerializeError::UnevenPad(field.label(), field.pad_with, remaining).into());
     }
 
-    v
+    let padding = pad.repeat(remaining / pad.len());
+    match field.justify {
+        Justify::Left => v.extend_from_slice(&padding),
+        _ => v = [padding, v].concat(),
+    }
+
+    Ok(v)
 }
 
 #[cfg(test)]
@@ -726,7 +1041,7 @@ mod test {
         let expected = ["12345".as_bytes(), "12345".as_bytes(), "123TT".as_bytes()];
 
         for (i, input) in inputs.iter().enumerate() {
-            let padded = pad(input, field);
+            let padded = pad(input, field, b"T").unwrap();
             assert_eq!(padded, expected[i].to_vec());
         }
     }
@@ -742,7 +1057,7 @@ mod test {
         let expected = ["12345".as_bytes(), "12345".as_bytes(), "TT123".as_bytes()];
 
         for (i, input) in inputs.iter().enumerate() {
-            let padded = pad(input, field);
+            let padded = pad(input, field, b"T").unwrap();
             println!("{:?}", padded);
             assert_eq!(padded, expected[i].to_vec());
         }
@@ -819,4 +1134,373 @@ mod test {
         let s = to_string(&test).unwrap();
         assert_eq!(s, "123abc987612 321cba678921 ".to_string());
     }
+
+    #[test]
+    fn encoded_ser() {
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4),
+            FieldSet::new_field(4..7)
+                .justify(Justify::Right)
+                .pad_with('0'),
+            FieldSet::new_field(7..9),
+        ]);
+
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields.clone()).encoding(Encoding::Cp037);
+            ("\u{e9}t\u{e9}", 42, ByteBuf::from(vec![0x12, 0x3C]))
+                .serialize(&mut ser)
+                .unwrap();
+        }
+
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(
+            bytes,
+            vec![0x51, 0xA3, 0x51, 0x40, 0xF0, 0xF4, 0xF2, 0x12, 0x3C]
+        );
+
+        let mut wrtr = Writer::from_memory();
+        let mut ser = Serializer::new(&mut wrtr, fields).encoding(Encoding::Latin1);
+
+        match "\u{20ac}".serialize(&mut ser) {
+            Err(Error::SerializeError(SerializeError::Unencodable(
+                '\u{20ac}',
+                Encoding::Latin1,
+            ))) => {}
+            r => panic!("expected an unencodable character error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn numeric_codec_ser() {
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2),
+            FieldSet::new_field(2..5).codec(Codec::Packed { signed: true }),
+            FieldSet::new_field(5..7).codec(Codec::Binary {
+                endian: crate::Endian::Big,
+                signed: true,
+            }),
+            FieldSet::new_field(7..9).codec(Codec::Zoned { signed: false }),
+        ]);
+
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields.clone()).encoding(Encoding::Cp037);
+            ("AB", -1234, -2_i16, 7_u8).serialize(&mut ser).unwrap();
+        }
+
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(
+            bytes,
+            vec![0xC1, 0xC2, 0x01, 0x23, 0x4D, 0xFF, 0xFE, 0xF0, 0xF7]
+        );
+
+        let mut wrtr = Writer::from_memory();
+        let mut ser = Serializer::new(&mut wrtr, fields);
+
+        match ("AB", 123_456, 0, 0).serialize(&mut ser) {
+            Err(Error::SerializeError(SerializeError::Overflow(_))) => {}
+            r => panic!("expected an overflow error, got {:?}", r),
+        }
+
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..3).codec(Codec::Zoned { signed: true }),
+            FieldSet::new_field(3..5).codec(Codec::Zoned { signed: false }),
+        ]);
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &(-42, 7), fields.clone()).unwrap();
+
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(bytes, b"04r07".to_vec());
+        assert_eq!(
+            crate::from_bytes_with_fields::<(i32, u8)>(&bytes, fields).unwrap(),
+            (-42, 7)
+        );
+    }
+
+    #[test]
+    fn overpunch_ser() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).codec(Codec::Overpunch),
+            FieldSet::new_field(4..8).codec(Codec::Overpunch),
+        ]);
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &(123, -450), fields.clone()).unwrap();
+        assert_eq!(Into::<Vec<u8>>::into(wrtr), b"012C045}".to_vec());
+
+        let mut wrtr = Writer::from_memory();
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields).encoding(Encoding::Cp037);
+            (123, -450).serialize(&mut ser).unwrap();
+        }
+
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(bytes, vec![0xF0, 0xF1, 0xF2, 0xC3, 0xF0, 0xF4, 0xF5, 0xD0]);
+    }
+
+    #[test]
+    fn int_128_ser() {
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..40).justify(Justify::Right),
+            FieldSet::new_field(40..56).codec(Codec::Binary {
+                endian: crate::Endian::Big,
+                signed: false,
+            }),
+        ]);
+        to_writer_with_fields(&mut wrtr, &(i128::MIN, u128::MAX), fields).unwrap();
+
+        let mut expected = format!("{:>40}", i128::MIN).into_bytes();
+        expected.extend_from_slice(&[0xFF; 16]);
+        assert_eq!(Into::<Vec<u8>>::into(wrtr), expected);
+    }
+
+    #[test]
+    fn sign_placement_ser() {
+        let fields = FieldSet::Seq(vec![FieldSet::new_field(0..5), FieldSet::new_field(5..10)])
+            .justify(Justify::Right)
+            .pad_with('0');
+        let cases = [
+            (Sign::Inline, "00-1200012"),
+            (Sign::Leading, "-001200012"),
+            (Sign::Trailing, "0012-00012"),
+            (Sign::Separate, "0012-0012+"),
+            (Sign::Always, "-0012+0012"),
+        ];
+
+        for (sign, expected) in cases.iter() {
+            let mut wrtr = Writer::from_memory();
+            let fields = fields.clone().sign(*sign);
+            to_writer_with_fields(&mut wrtr, &(-12, 12), fields).unwrap();
+
+            assert_eq!(Into::<String>::into(wrtr), *expected);
+        }
+
+        for sign in [Sign::Always, Sign::Separate] {
+            for width in [0, 1] {
+                let mut wrtr = Writer::from_memory();
+                let field = FieldSet::new_field(0..width).sign(sign);
+
+                match to_writer_with_fields(&mut wrtr, &-1, field) {
+                    Err(Error::SerializeError(SerializeError::FieldOverflow(_, val, _))) => {
+                        assert_eq!(val, "-1")
+                    }
+                    r => panic!("expected a field overflow, got {:?}", r),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn boolean_mapping_ser() {
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..1).booleans(crate::Booleans::yes_no().or_true("y")),
+            FieldSet::new_field(1..2).booleans(crate::Booleans::flag("X")),
+            FieldSet::new_field(2..5).booleans(crate::Booleans::new("YES", "NO")),
+        ]);
+
+        to_writer_with_fields(&mut wrtr, &(true, true, true), fields.clone()).unwrap();
+        to_writer_with_fields(&mut wrtr, &(false, false, false), fields).unwrap();
+        let s: String = wrtr.into();
+
+        assert_eq!(s, "YXYESN NO ");
+    }
+
+    #[test]
+    fn overflow_ser() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4).name("name"),
+            FieldSet::new_field(4..7)
+                .justify(Justify::Right)
+                .name("amount"),
+        ]);
+
+        let mut wrtr = Writer::from_memory();
+        let err = to_writer_with_fields(&mut wrtr, &("Rosalind", 12345), fields.clone());
+        match err {
+            Err(Error::SerializeError(SerializeError::FieldOverflow(name, val, width))) => {
+                assert_eq!((name.as_str(), val.as_str(), width), ("amount", "12345", 3));
+            }
+            _ => panic!("expected FieldOverflow, got {:?}", err),
+        }
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &("Rosalind", 123), fields.clone()).unwrap();
+        assert_eq!(Into::<String>::into(wrtr), "Rosa123");
+
+        let mut wrtr = Writer::from_memory();
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields).overflow(Overflow::TruncateLeft);
+            ("Rosalind", 12345).serialize(&mut ser).unwrap();
+        }
+        assert_eq!(Into::<String>::into(wrtr), "lind345");
+
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2).overflow(Overflow::Ellipsis),
+            FieldSet::new_field(2..6).sign(Sign::Trailing),
+            FieldSet::new_field(6..9).scale(2),
+        ]);
+        let mut wrtr = Writer::from_memory();
+        assert!(to_writer_with_fields(&mut wrtr, &("abc", -1234, "0.5"), fields.clone()).is_err());
+        assert!(to_writer_with_fields(&mut wrtr, &("abc", -123, "10.5"), fields.clone()).is_err());
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &("abc", -123, "1.5"), fields).unwrap();
+        assert_eq!(Into::<String>::into(wrtr), "..123-150");
+    }
+
+    #[test]
+    fn overflow_at_char_boundary_ser() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..4),
+            FieldSet::new_field(4..8)
+                .justify(Justify::Right)
+                .overflow(Overflow::TruncateLeft),
+            FieldSet::new_field(8..13).overflow(Overflow::Ellipsis),
+        ]);
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &("abcé", "éabc", "aééé"), fields).unwrap();
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "abc  abca... ");
+    }
+
+    #[test]
+    fn multi_byte_pad_ser() {
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..7).pad_with('·'),
+            FieldSet::new_field(7..11)
+                .pad_with('·')
+                .justify(Justify::Right),
+        ]);
+
+        let mut wrtr = Writer::from_memory();
+        to_writer_with_fields(&mut wrtr, &("abc", "12"), fields.clone()).unwrap();
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(String::from_utf8(bytes.clone()).unwrap(), "abc···12");
+
+        let values: (String, String) =
+            crate::from_bytes_with_fields(&bytes, fields.clone()).unwrap();
+        assert_eq!(values, ("abc".to_string(), "12".to_string()));
+
+        let mut wrtr = Writer::from_memory();
+        let err = to_writer_with_fields(&mut wrtr, &("ab", "12"), fields.clone());
+        match err {
+            Err(Error::SerializeError(SerializeError::UnevenPad(name, '·', 5))) => {
+                assert_eq!(name, "0..7")
+            }
+            _ => panic!("expected UnevenPad, got {:?}", err),
+        }
+
+        let mut wrtr = Writer::from_memory();
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields).encoding(Encoding::Latin1);
+            ("ab", "12").serialize(&mut ser).unwrap();
+        }
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(bytes, b"ab\xB7\xB7\xB7\xB7\xB7\xB7\xB712".to_vec());
+    }
+
+    #[test]
+    fn tagged_enum_ser() {
+        #[derive(Serialize)]
+        enum Tagged {
+            Header { date: String },
+            Detail(String, u32),
+            Total(u32),
+            Trailer,
+            Unknown,
+        }
+
+        let fields = FieldSet::new_field(0..1)
+            .variant(
+                "H",
+                "Header",
+                FieldSet::Seq(vec![FieldSet::new_field(1..9)]),
+            )
+            .variant(
+                "D",
+                "Detail",
+                FieldSet::Seq(vec![
+                    FieldSet::new_field(1..5),
+                    FieldSet::new_field(5..9)
+                        .justify(Justify::Right)
+                        .pad_with('0'),
+                ]),
+            )
+            .variant(
+                "S",
+                "Total",
+                FieldSet::new_field(1..9)
+                    .justify(Justify::Right)
+                    .pad_with('0'),
+            )
+            .variant("T", "Trailer", FieldSet::Seq(vec![]));
+
+        let records = [
+            Tagged::Header {
+                date: "20240131".to_string(),
+            },
+            Tagged::Detail("Ann".to_string(), 12),
+            Tagged::Total(42),
+            Tagged::Trailer,
+        ];
+        let mut wrtr = Writer::from_memory();
+        for record in records.iter() {
+            to_writer_with_fields(&mut wrtr, record, fields.clone()).unwrap();
+        }
+        assert_eq!(Into::<String>::into(wrtr), "H20240131DAnn 0012S00000042T");
+
+        let mut wrtr = Writer::from_memory();
+        let err = to_writer_with_fields(&mut wrtr, &Tagged::Unknown, fields);
+        assert!(matches!(
+            err,
+            Err(Error::SerializeError(SerializeError::UnknownVariant(_)))
+        ));
+    }
+
+    #[test]
+    fn null_representations_ser() {
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::Seq(vec![
+            FieldSet::new_field(0..2).null(Null::Byte(0xFF)),
+            FieldSet::new_field(2..6).null(Null::Fill('9')),
+            FieldSet::new_field(6..12)
+                .justify(Justify::Right)
+                .null(Null::Text("NULL".to_string())),
+        ]);
+        let none: (Option<u8>, Option<u32>, Option<String>) = (None, None, None);
+
+        {
+            let mut ser = Serializer::new(&mut wrtr, fields).encoding(Encoding::Cp037);
+            none.serialize(&mut ser).unwrap();
+        }
+
+        let bytes: Vec<u8> = wrtr.into();
+        assert_eq!(
+            bytes,
+            vec![0xFF, 0xFF, 0xF9, 0xF9, 0xF9, 0xF9, 0x40, 0x40, 0xD5, 0xE4, 0xD3, 0xD3]
+        );
+
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::new_field(0..3).null(Null::Text("NULL".to_string()));
+        match to_writer_with_fields(&mut wrtr, &None::<String>, fields) {
+            Err(Error::SerializeError(SerializeError::FieldOverflow(name, val, width))) => {
+                assert_eq!((name.as_str(), val.as_str(), width), ("0..3", "NULL", 3));
+            }
+            r => panic!("expected FieldOverflow, got {:?}", r),
+        }
+
+        let mut wrtr = Writer::from_memory();
+        let fields = FieldSet::new_field(0..3).null(Null::Fill('é'));
+        match to_writer_with_fields(&mut wrtr, &None::<String>, fields) {
+            Err(Error::SerializeError(SerializeError::UnevenPad(name, c, remaining))) => {
+                assert_eq!((name.as_str(), c, remaining), ("0..3", 'é', 3));
+            }
+            r => panic!("expected UnevenPad, got {:?}", r),
+        }
+    }
 }
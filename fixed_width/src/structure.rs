@@ -0,0 +1,594 @@
+use crate::{de, DeserializeError, Encoding, FieldConfig, FieldSet, Result};
+use std::{error::Error as StdError, fmt, ops::Range};
+
+/// An error produced when a stream of records does not follow its `Structure`, or when a
+/// `Structure` is invalid. Each variant found in a stream contains the index of the record where
+/// the structure breaks, counted from `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructureError {
+    /// A record whose code is not allowed where it appears. Contains the index and the code of the
+    /// record.
+    UnexpectedRecord(usize, String),
+    /// The records ended before a header or a trailer. Contains the index where the record was
+    /// expected and its code.
+    MissingRecord(usize, String),
+    /// A trailer field does not match the records of its group. Contains the index of the trailer,
+    /// the range of the field, the expected value and the text of the field.
+    TotalMismatch(usize, Range<usize>, i128, String),
+    /// A field that is counted or summed could not be read as an integer. Contains the index of the
+    /// record, the range of the field and its text.
+    InvalidNumber(usize, Range<usize>, String),
+    /// A total of a group is not a single field. Contains the header code of the group and the
+    /// number of fields given for the total.
+    InvalidTotal(String, usize),
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StructureError::UnexpectedRecord(index, ref code) => {
+                write!(f, "record {}: unexpected record type `{}`", index, code)
+            }
+            StructureError::MissingRecord(index, ref code) => {
+                write!(f, "record {}: expected a record of type `{}`", index, code)
+            }
+            StructureError::TotalMismatch(index, ref range, expected, ref found) => write!(
+                f,
+                "record {}: field {}..{} is `{}`, expected {}",
+                index, range.start, range.end, found, expected
+            ),
+            StructureError::InvalidNumber(index, ref range, ref text) => write!(
+                f,
+                "record {}: field {}..{} is not a number: `{}`",
+                index, range.start, range.end, text
+            ),
+            StructureError::InvalidTotal(ref header, count) => write!(
+                f,
+                "group `{}`: a total must be a single field, got {} fields",
+                header, count
+            ),
+        }
+    }
+}
+
+impl StdError for StructureError {
+    fn cause(&self) -> Option<&dyn StdError> {
+        None
+    }
+}
+
+// The fields of a total are kept as given, and checked to be single fields by `Structure::new`.
+#[derive(Debug, Clone)]
+enum Total {
+    Count {
+        field: Vec<FieldConfig>,
+        codes: Vec<String>,
+    },
+    Sum {
+        field: Vec<FieldConfig>,
+        code: String,
+        detail: Vec<FieldConfig>,
+    },
+}
+
+// The only field of a total, once the structure is checked.
+fn single(field: &[FieldConfig]) -> &FieldConfig {
+    &field[0]
+}
+
+/// A group of records opened by a header record and closed by a trailer record, such as a file or
+/// a batch of a file. Between them, a group holds any number of detail records and nested groups,
+/// in any order.
+///
+/// A group may declare totals: fields of its trailer that must equal the number of records of
+/// some types, or the sum of a field of some records, among all the records it holds. The records
+/// of nested groups are included, but not the header and trailer of the group itself.
+///
+/// The fields of totals are either a byte range, read as plain text, or a single field read with
+/// its codec and sign policy, such as an overpunched amount. The scale of a field is left out, so
+/// a scaled amount is summed as its digits.
+#[derive(Debug, Clone)]
+pub struct Group {
+    header: String,
+    trailer: String,
+    records: Vec<String>,
+    groups: Vec<Group>,
+    totals: Vec<Total>,
+}
+
+impl Group {
+    /// Creates a group opened by a record with the code `header` and closed by a record with the
+    /// code `trailer`.
+    pub fn new<T: Into<String>>(header: T, trailer: T) -> Self {
+        Self {
+            header: header.into(),
+            trailer: trailer.into(),
+            records: vec![],
+            groups: vec![],
+            totals: vec![],
+        }
+    }
+
+    /// Allows detail records with the given code in the group.
+    pub fn record<T: Into<String>>(mut self, code: T) -> Self {
+        self.records.push(code.into());
+        self
+    }
+
+    /// Allows a nested group in the group. A record whose code is allowed as a detail record is
+    /// never read as the header of a nested group.
+    pub fn group(mut self, group: Group) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Requires the `field` of the trailer to hold the number of records of the group with one of
+    /// the given codes. `field` must be a single field, see `Structure::new`.
+    pub fn count<F: Into<FieldSet>>(mut self, field: F, codes: &[&str]) -> Self {
+        self.totals.push(Total::Count {
+            field: field.into().flatten(),
+            codes: codes.iter().map(|c| c.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Requires the `field` of the trailer to hold the sum of the `detail` field of the records of
+    /// the group with the given code. `field` and `detail` must be single fields, see
+    /// `Structure::new`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Codec, FieldSet, Group, Reader, Sign, Structure};
+    ///
+    /// let amount = FieldSet::new_field(1..4).codec(Codec::Overpunch);
+    /// let total = FieldSet::new_field(1..5).sign(Sign::Trailing);
+    /// let group = Group::new("H", "T").record("D").sum(total, "D", amount);
+    /// let structure = Structure::new(0..1, group).unwrap();
+    ///
+    /// let mut reader = Reader::from_string("H    D01B D01R T007-").width(5);
+    /// assert!(reader.validate_structure(&structure).is_ok());
+    /// ```
+    pub fn sum<F, T, D>(mut self, field: F, code: T, detail: D) -> Self
+    where
+        F: Into<FieldSet>,
+        T: Into<String>,
+        D: Into<FieldSet>,
+    {
+        self.totals.push(Total::Sum {
+            field: field.into().flatten(),
+            code: code.into(),
+            detail: detail.into().flatten(),
+        });
+        self
+    }
+}
+
+/// The structure of a file made of nested groups of records, such as a file header and trailer
+/// around batches that each have their own header and trailer. The type of each record is given by
+/// a code held in the same byte range of every record.
+///
+/// A stream of records is valid when it is exactly one instance of the root group, and every total
+/// declared by a group matches its records.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{Error, Group, Reader, Structure, StructureError};
+///
+/// let batch = Group::new("5", "8")
+///     .record("6")
+///     .count(1..3, &["6"])
+///     .sum(3..8, "6", 1..6);
+/// let structure =
+///     Structure::new(0..1, Group::new("1", "9").group(batch).count(1..3, &["5"])).unwrap();
+///
+/// let data = "1HEADER 5BATCH016000120X6000030X8020001590100000";
+/// let mut reader = Reader::from_string(data).width(8);
+/// assert!(structure.validate(reader.byte_reader()).is_ok());
+///
+/// // The batch trailer says it holds three entries.
+/// let data = "1HEADER 5BATCH016000120X6000030X8030001590100000";
+/// let mut reader = Reader::from_string(data).width(8);
+///
+/// match structure.validate(reader.byte_reader()) {
+///     Err(Error::StructureError(StructureError::TotalMismatch(index, _, expected, _))) => {
+///         assert_eq!((index, expected), (4, 2));
+///     }
+///     _ => panic!("expected a total mismatch"),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Structure {
+    range: Range<usize>,
+    encoding: Option<Encoding>,
+    root: Group,
+}
+
+impl Structure {
+    /// Creates a structure whose records hold their code in the given byte range, and that is made
+    /// of a single `root` group. Fails with `StructureError::InvalidTotal` if the field of a total
+    /// of any group is not a single field.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Error, FieldSet, Group, Structure, StructureError};
+    ///
+    /// let total = FieldSet::Seq(vec![FieldSet::new_field(1..3), FieldSet::new_field(3..5)]);
+    ///
+    /// match Structure::new(0..1, Group::new("H", "T").record("D").count(total, &["D"])) {
+    ///     Err(Error::StructureError(StructureError::InvalidTotal(header, 2))) => {
+    ///         assert_eq!(header, "H");
+    ///     }
+    ///     _ => panic!("expected an invalid total"),
+    /// }
+    /// ```
+    pub fn new(range: Range<usize>, root: Group) -> Result<Self> {
+        check_totals(&root)?;
+
+        Ok(Self {
+            range,
+            encoding: None,
+            root,
+        })
+    }
+
+    /// Sets the encoding of the records, used to read their code and the fields of their totals.
+    /// By default, records are read in the encoding of the `Reader` they come from, see
+    /// `Reader::validate_structure`, or in `Encoding::Utf8`.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Returns a validator that checks the records of a stream one at a time, so that they can be
+    /// validated while they are read.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Group, Reader, Structure};
+    ///
+    /// let structure = Structure::new(0..1, Group::new("H", "T").record("D")).unwrap();
+    /// let mut validator = structure.validator();
+    /// let mut reader = Reader::from_string("HDDT").width(1);
+    ///
+    /// while let Some(record) = reader.next_record() {
+    ///     validator.push(record.unwrap()).unwrap();
+    /// }
+    ///
+    /// assert!(validator.finish().is_ok());
+    /// ```
+    pub fn validator(&self) -> StructureValidator<'_> {
+        self.validator_in(Encoding::default())
+    }
+
+    // Returns a validator that reads records in the given encoding, unless the structure has its
+    // own.
+    pub(crate) fn validator_in(&self, encoding: Encoding) -> StructureValidator<'_> {
+        StructureValidator {
+            structure: self,
+            encoding: self.encoding.unwrap_or(encoding),
+            index: 0,
+            open: vec![],
+            closed: false,
+        }
+    }
+
+    /// Validates a whole stream of records, such as the records of `Reader::byte_reader`. Fails
+    /// with the first `StructureError` found, or with the first error of the stream.
+    pub fn validate<I>(&self, records: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<Vec<u8>>>,
+    {
+        let mut validator = self.validator();
+
+        for record in records {
+            validator.push(&record?)?;
+        }
+
+        validator.finish()
+    }
+}
+
+fn check_totals(group: &Group) -> Result<()> {
+    for total in &group.totals {
+        let fields = match total {
+            Total::Count { field, .. } => vec![field],
+            Total::Sum { field, detail, .. } => vec![field, detail],
+        };
+
+        if let Some(field) = fields.into_iter().find(|f| f.len() != 1) {
+            return Err(StructureError::InvalidTotal(group.header.clone(), field.len()).into());
+        }
+    }
+
+    group.groups.iter().try_for_each(check_totals)
+}
+
+#[derive(Debug)]
+struct OpenGroup<'a> {
+    group: &'a Group,
+    totals: Vec<i128>,
+}
+
+/// Validates records one at a time against a `Structure`, see `Structure::validator`.
+#[derive(Debug)]
+pub struct StructureValidator<'a> {
+    structure: &'a Structure,
+    encoding: Encoding,
+    index: usize,
+    open: Vec<OpenGroup<'a>>,
+    closed: bool,
+}
+
+impl<'a> StructureValidator<'a> {
+    /// Checks the next record of the stream.
+    pub fn push(&mut self, record: &[u8]) -> Result<()> {
+        let code = self.text(record, &self.structure.range)?;
+        let index = self.index;
+        self.index += 1;
+
+        let group = match self.open.last() {
+            Some(open) => open.group,
+            None if !self.closed && code == self.structure.root.header => {
+                self.open(&self.structure.root);
+                return Ok(());
+            }
+            None => return Err(StructureError::UnexpectedRecord(index, code).into()),
+        };
+
+        if code == group.trailer {
+            let open = self.open.pop().expect("an open group");
+            self.check_totals(&open, record, index)?;
+            self.closed = self.open.is_empty();
+            self.tally(&code, record, index)
+        } else if group.records.contains(&code) {
+            self.tally(&code, record, index)
+        } else if let Some(nested) = group.groups.iter().find(|g| g.header == code) {
+            self.tally(&code, record, index)?;
+            self.open(nested);
+            Ok(())
+        } else {
+            Err(StructureError::UnexpectedRecord(index, code).into())
+        }
+    }
+
+    /// Checks that the stream has ended with the trailer of the root group.
+    pub fn finish(self) -> Result<()> {
+        let expected = match self.open.last() {
+            Some(open) => &open.group.trailer,
+            None if !self.closed => &self.structure.root.header,
+            None => return Ok(()),
+        };
+
+        Err(StructureError::MissingRecord(self.index, expected.clone()).into())
+    }
+
+    fn open(&mut self, group: &'a Group) {
+        self.open.push(OpenGroup {
+            group,
+            totals: vec![0; group.totals.len()],
+        });
+    }
+
+    fn tally(&mut self, code: &str, record: &[u8], index: usize) -> Result<()> {
+        for i in 0..self.open.len() {
+            for j in 0..self.open[i].group.totals.len() {
+                let value = match self.open[i].group.totals[j] {
+                    Total::Count { ref codes, .. } if codes.iter().any(|c| c == code) => 1,
+                    Total::Sum {
+                        code: ref c,
+                        ref detail,
+                        ..
+                    } if c == code => self.number(record, single(detail), index)?,
+                    _ => 0,
+                };
+
+                self.open[i].totals[j] += value;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_totals(&self, open: &OpenGroup, record: &[u8], index: usize) -> Result<()> {
+        for (total, &expected) in open.group.totals.iter().zip(&open.totals) {
+            let field = match total {
+                Total::Count { field, .. } | Total::Sum { field, .. } => single(field),
+            };
+
+            if self.number(record, field, index)? != expected {
+                let text = self.shown(record, &field.range);
+                return Err(StructureError::TotalMismatch(
+                    index,
+                    field.range.clone(),
+                    expected,
+                    text,
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn number(&self, record: &[u8], field: &FieldConfig, index: usize) -> Result<i128> {
+        if record.get(field.range.clone()).is_none() {
+            return Err(DeserializeError::UnexpectedEndOfRecord.into());
+        }
+
+        match de::read_integer(record, field, self.encoding) {
+            Ok(Some(value)) => Ok(value),
+            _ => {
+                let text = self.shown(record, &field.range);
+                Err(StructureError::InvalidNumber(index, field.range.clone(), text).into())
+            }
+        }
+    }
+
+    // The text of a field for an error, which may hold binary numbers that do not decode.
+    fn shown(&self, record: &[u8], range: &Range<usize>) -> String {
+        let bytes = &record[range.clone()];
+
+        match self.encoding.decode(bytes) {
+            Ok(text) => text.into_owned(),
+            Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+
+    fn text(&self, record: &[u8], range: &Range<usize>) -> Result<String> {
+        let bytes = record
+            .get(range.clone())
+            .ok_or(DeserializeError::UnexpectedEndOfRecord)?;
+        let text = self
+            .encoding
+            .decode(bytes)
+            .map_err(DeserializeError::from)?;
+
+        Ok(text.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, Reader};
+
+    fn structure() -> Structure {
+        let batch = Group::new("5", "8")
+            .record("6")
+            .record("7")
+            .count(1..3, &["6", "7"])
+            .sum(3..8, "6", 1..6);
+        let file = Group::new("1", "9")
+            .group(batch)
+            .count(1..3, &["5"])
+            .count(3..5, &["6", "7"])
+            .sum(5..10, "6", 1..6);
+
+        Structure::new(0..1, file).unwrap()
+    }
+
+    fn validate(records: &[&str]) -> Result<()> {
+        let data = records.concat();
+        let mut reader = Reader::from_string(data).width(10);
+        structure().validate(reader.byte_reader())
+    }
+
+    fn structure_error(records: &[&str]) -> StructureError {
+        match validate(records) {
+            Err(Error::StructureError(e)) => e,
+            other => panic!("expected a structure error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_structure() {
+        validate(&[
+            "1HEADER   ",
+            "5BATCH1   ",
+            "600012    ",
+            "7ADDENDA  ",
+            "600003    ",
+            "80300015  ",
+            "5BATCH2   ",
+            "600100    ",
+            "80100100  ",
+            "9020400115",
+        ])
+        .unwrap();
+
+        validate(&["1HEADER   ", "9000000000"]).unwrap();
+    }
+
+    #[test]
+    fn breaks_in_structure() {
+        assert_eq!(
+            structure_error(&["5BATCH1   "]),
+            StructureError::UnexpectedRecord(0, "5".to_string())
+        );
+        assert_eq!(
+            structure_error(&["1HEADER   ", "600012    "]),
+            StructureError::UnexpectedRecord(1, "6".to_string())
+        );
+        assert_eq!(
+            structure_error(&["1HEADER   ", "5BATCH1   ", "600012    "]),
+            StructureError::MissingRecord(3, "8".to_string())
+        );
+        assert_eq!(
+            structure_error(&["1HEADER   ", "9000000000", "1HEADER   "]),
+            StructureError::UnexpectedRecord(2, "1".to_string())
+        );
+        assert_eq!(
+            structure_error(&[]),
+            StructureError::MissingRecord(0, "1".to_string())
+        );
+    }
+
+    #[test]
+    fn mismatched_totals() {
+        assert_eq!(
+            structure_error(&[
+                "1HEADER   ",
+                "5BATCH1   ",
+                "600012    ",
+                "80100013  ",
+                "9010100012",
+            ]),
+            StructureError::TotalMismatch(3, 3..8, 12, "00013".to_string())
+        );
+        assert_eq!(
+            structure_error(&[
+                "1HEADER   ",
+                "5BATCH1   ",
+                "600012    ",
+                "80100012  ",
+                "9020100012",
+            ]),
+            StructureError::TotalMismatch(4, 1..3, 1, "02".to_string())
+        );
+        assert_eq!(
+            structure_error(&["1HEADER   ", "5BATCH1   ", "6ABCDE    "]),
+            StructureError::InvalidNumber(2, 1..6, "ABCDE".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_totals() {
+        let detail = FieldSet::Seq(vec![FieldSet::new_field(1..3), FieldSet::new_field(3..6)]);
+        let batch = Group::new("5", "8").record("6").sum(3..8, "6", detail);
+
+        match Structure::new(0..1, Group::new("1", "9").group(batch)) {
+            Err(Error::StructureError(e)) => {
+                assert_eq!(e, StructureError::InvalidTotal("5".to_string(), 2))
+            }
+            other => panic!("expected an invalid total, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn negative_totals() {
+        use crate::{Codec, Sign};
+
+        let amount = FieldSet::new_field(1..6).codec(Codec::Overpunch);
+        let total = FieldSet::new_field(3..8).sign(Sign::Trailing);
+        let batch = Group::new("5", "8").record("6").sum(total, "6", amount);
+        let structure = Structure::new(0..1, batch).unwrap();
+        let validate = |records: &[&str]| {
+            let mut reader = Reader::from_string(records.concat()).width(10);
+            structure.validate(reader.byte_reader())
+        };
+
+        validate(&["5BATCH1   ", "60001B    ", "60001R    ", "8  0007-  "]).unwrap();
+
+        match validate(&["5BATCH1   ", "60001B    ", "8  0012-  "]) {
+            Err(Error::StructureError(e)) => assert_eq!(
+                e,
+                StructureError::TotalMismatch(2, 3..8, 12, "0012-".to_string())
+            ),
+            other => panic!("expected a structure error, got {:?}", other),
+        }
+    }
+}
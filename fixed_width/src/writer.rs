@@ -1,7 +1,12 @@
-use crate::{ser, FixedWidth, LineBreak, Result};
+use crate::{
+    de,
+    ser::{SerializeError, Serializer},
+    Codec, Encoding, FieldConfig, FieldSet, FixedWidth, LineBreak, Result, Sign,
+};
 use serde::ser::Serialize;
 use std::{
     borrow::Cow,
+    collections::HashMap,
     io::{self, Write},
 };
 
@@ -56,7 +61,7 @@ where
     }
 }
 
-impl<'a, T: ?Sized + AsByteSlice> AsByteSlice for &'a T {
+impl<T: ?Sized + AsByteSlice> AsByteSlice for &T {
     fn as_byte_slice(&self) -> &[u8] {
         (*self).as_byte_slice()
     }
@@ -86,6 +91,7 @@ impl<'a, T: ?Sized + AsByteSlice> AsByteSlice for &'a T {
 pub struct Writer<W: Write> {
     wrtr: io::BufWriter<W>,
     linebreak: LineBreak,
+    encoding: Encoding,
 }
 
 impl<W> Writer<W>
@@ -102,6 +108,7 @@ where
         Self {
             wrtr: buf,
             linebreak: LineBreak::None,
+            encoding: Encoding::default(),
         }
     }
 
@@ -120,14 +127,16 @@ where
                 first_record = false;
             }
 
-            ser::to_writer(self, &record)?;
+            let encoding = self.encoding;
+            record.serialize(&mut Serializer::new(self, T::fields()).encoding(encoding))?;
         }
 
         Ok(())
     }
 
     /// Writes the given iterator of types that implement AsByteSlice to the underlying writer,
-    /// optionally inserting linebreaks if specified.
+    /// optionally inserting linebreaks if specified. The records are written as is, without
+    /// being transcoded to the writer's encoding.
     pub fn write_iter<T: AsByteSlice>(&mut self, records: impl Iterator<Item = T>) -> Result<()> {
         let mut first_record = true;
 
@@ -148,15 +157,14 @@ where
     /// linebreak.
     #[inline]
     pub fn write_linebreak(&mut self) -> Result<()> {
-        match self.linebreak {
-            LineBreak::Newline => {
-                self.write_all(b"\n")?;
-            }
-            LineBreak::CRLF => {
-                self.write_all(b"\r\n")?;
-            }
-            LineBreak::None => {}
-        }
+        let linebreak = match self.linebreak {
+            LineBreak::Newline => "\n",
+            LineBreak::CRLF => "\r\n",
+            LineBreak::None => return Ok(()),
+        };
+
+        let bytes = self.encoding.encode(linebreak)?;
+        self.write_all(&bytes)?;
 
         Ok(())
     }
@@ -166,6 +174,355 @@ where
         self.linebreak = linebreak;
         self
     }
+
+    /// Starts writing groups of records whose trailers carry control totals, see `TotalsWriter`.
+    pub fn with_totals(&mut self) -> TotalsWriter<'_, W> {
+        TotalsWriter {
+            wrtr: self,
+            first_record: true,
+            root: Totals::default(),
+            groups: vec![],
+        }
+    }
+
+    /// Sets the encoding that serialized records and linebreaks are written in. Defaults to
+    /// `Encoding::Utf8`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// use fixed_width::{Encoding, FieldSet, FixedWidth, LineBreak, Writer};
+    /// use serde_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Code(String);
+    ///
+    /// impl FixedWidth for Code {
+    ///     fn fields() -> FieldSet {
+    ///         FieldSet::new_field(0..3)
+    ///     }
+    /// }
+    ///
+    /// let mut wrtr = Writer::from_memory()
+    ///     .linebreak(LineBreak::Newline)
+    ///     .encoding(Encoding::Cp037);
+    ///
+    /// wrtr.write_serialized(vec![Code("AB".into()), Code("C".into())].into_iter())
+    ///     .unwrap();
+    ///
+    /// let bytes: Vec<u8> = wrtr.into();
+    /// assert_eq!(bytes, vec![0xC1, 0xC2, 0x40, 0x25, 0xC3, 0x40, 0x40]);
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+}
+
+/// The running aggregates of the detail records written in a group, used to fill its trailer.
+///
+/// Sums are kept for every named field of the details, read back through the codec and sign policy
+/// of the field, so overpunched, packed and binary amounts are summed as the numbers they hold.
+/// Numbers are summed as they are written, with any implied decimal point left out, and blank or
+/// null fields count as `0`.
+///
+/// A field with a numeric codec or a sign policy must read as an integer, or writing the detail
+/// fails. A plain text field, such as a name, can only be summed if it reads as an integer in
+/// every detail record, and `sum` fails for it otherwise.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Totals {
+    records: usize,
+    groups: usize,
+    sums: HashMap<String, Sum>,
+}
+
+// The running sum of a field, or why it can not be summed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sum {
+    Value(i128),
+    NotInteger,
+    Overflow,
+}
+
+impl Totals {
+    /// The number of detail records in the group, including those of nested groups.
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// The number of groups nested directly in the group.
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// The sum of the field with the given name over the detail records of the group, including
+    /// those of nested groups. Returns `0` if no detail record has that field. Fails if the field
+    /// is plain text that is not an integer in some detail record, or if the sum overflows.
+    pub fn sum(&self, name: &str) -> Result<i128> {
+        let message = match self.sums.get(name) {
+            None => return Ok(0),
+            Some(Sum::Value(sum)) => return Ok(*sum),
+            Some(Sum::NotInteger) => "is not an integer in every detail record",
+            Some(Sum::Overflow) => "has a sum that does not fit in an i128",
+        };
+
+        Err(SerializeError::Message(format!("field `{}` {}", name, message)).into())
+    }
+
+    /// The sum of the field with the given name, keeping only its last `digits` digits, such as the
+    /// entry hash of a NACHA batch. Fails as `sum` does, or if `digits` is over 38.
+    pub fn hash(&self, name: &str, digits: u32) -> Result<i128> {
+        let modulus = 10i128.checked_pow(digits).ok_or_else(|| {
+            SerializeError::Message(format!("a hash can not keep {} digits", digits))
+        })?;
+
+        Ok(self.sum(name)?.rem_euclid(modulus))
+    }
+
+    fn add(&mut self, other: &Totals) {
+        self.records += other.records;
+        self.groups += 1;
+
+        for (name, &sum) in &other.sums {
+            self.add_sum(name.clone(), sum);
+        }
+    }
+
+    fn add_sum(&mut self, name: String, value: Sum) {
+        let sum = self.sums.entry(name).or_insert(Sum::Value(0));
+        *sum = match (*sum, value) {
+            (Sum::Value(a), Sum::Value(b)) => a.checked_add(b).map_or(Sum::Overflow, Sum::Value),
+            (Sum::Value(_), other) | (other, _) => other,
+        };
+    }
+}
+
+type TrailerFn<'a> = Box<dyn FnOnce(&Totals, Encoding) -> Result<Vec<u8>> + 'a>;
+
+struct OpenGroup<'a> {
+    totals: Totals,
+    trailer: TrailerFn<'a>,
+}
+
+/// Writes nested groups of records, such as batches within a file, and fills the trailer of each
+/// group from the `Totals` of the detail records written in it. Details are written as they come,
+/// so they do not have to be buffered to compute the trailers.
+///
+/// Each group is opened with its header and a function that builds its trailer from its totals.
+/// The trailer is written when the group is closed, and `finish` closes the groups that are still
+/// open.
+///
+/// ### Example
+///
+/// ```rust
+/// use fixed_width::{FieldSet, FixedWidth, LineBreak, Writer};
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Header {
+///     kind: &'static str,
+/// }
+///
+/// impl FixedWidth for Header {
+///     fn fields() -> FieldSet {
+///         FieldSet::new_field(0..1)
+///     }
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Entry {
+///     kind: &'static str,
+///     amount: u32,
+/// }
+///
+/// impl FixedWidth for Entry {
+///     fn fields() -> FieldSet {
+///         FieldSet::Seq(vec![
+///             FieldSet::new_field(0..1),
+///             FieldSet::new_field(1..6).name("amount").pad_with('0').justify("right"),
+///         ])
+///     }
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Trailer {
+///     kind: &'static str,
+///     count: usize,
+///     total: i128,
+/// }
+///
+/// impl FixedWidth for Trailer {
+///     fn fields() -> FieldSet {
+///         FieldSet::Seq(vec![
+///             FieldSet::new_field(0..1),
+///             FieldSet::new_field(1..3).pad_with('0').justify("right"),
+///             FieldSet::new_field(3..8).pad_with('0').justify("right"),
+///         ])
+///     }
+/// }
+///
+/// let mut wrtr = Writer::from_memory().linebreak(LineBreak::Newline);
+/// let mut totals = wrtr.with_totals();
+///
+/// totals
+///     .open(&Header { kind: "5" }, |t| {
+///         Ok(Trailer {
+///             kind: "8",
+///             count: t.records(),
+///             total: t.sum("amount")?,
+///         })
+///     })
+///     .unwrap();
+/// totals.write(&Entry { kind: "6", amount: 12 }).unwrap();
+/// totals.write(&Entry { kind: "6", amount: 30 }).unwrap();
+/// totals.finish().unwrap();
+///
+/// let s: String = wrtr.into();
+/// assert_eq!(s, "5\n600012\n600030\n80200042");
+/// ```
+pub struct TotalsWriter<'w, W: Write> {
+    wrtr: &'w mut Writer<W>,
+    first_record: bool,
+    root: Totals,
+    groups: Vec<OpenGroup<'w>>,
+}
+
+impl<'w, W> TotalsWriter<'w, W>
+where
+    W: Write,
+{
+    /// Writes the header of a new group, nested in the innermost open group. The trailer of the
+    /// group is built by `trailer` from the totals of the group when it is closed, and closing the
+    /// group fails with the error of `trailer`, such as a sum that can not be computed.
+    pub fn open<H, T, F>(&mut self, header: &H, trailer: F) -> Result<()>
+    where
+        H: FixedWidth + Serialize,
+        T: FixedWidth + Serialize,
+        F: FnOnce(&Totals) -> Result<T> + 'w,
+    {
+        let bytes = serialize(header, H::fields(), self.wrtr.encoding)?;
+        self.write_record(&bytes)?;
+
+        self.groups.push(OpenGroup {
+            totals: Totals::default(),
+            trailer: Box::new(move |totals, encoding| {
+                serialize(&trailer(totals)?, T::fields(), encoding)
+            }),
+        });
+
+        Ok(())
+    }
+
+    /// Writes a detail record in the innermost open group and adds it to the totals of the group.
+    /// Fails without writing the detail if one of its numeric fields does not read back as an
+    /// integer.
+    pub fn write<T: FixedWidth + Serialize>(&mut self, detail: &T) -> Result<()> {
+        let fields = T::fields();
+        let encoding = self.wrtr.encoding;
+        let bytes = serialize(detail, fields.clone(), encoding)?;
+
+        let mut sums = vec![];
+        let mut offset = 0;
+        let mut fields = fields.flatten();
+        let mut i = 0;
+        while i < fields.len() {
+            let mut field = fields[i].clone();
+            let start = offset;
+            offset += field.width();
+            i += 1;
+
+            // The fields of the variant that was written follow its tag.
+            let variant = bytes
+                .get(start..offset)
+                .and_then(|tag| encoding.decode(tag).ok())
+                .and_then(|tag| {
+                    let tag = tag.trim();
+                    field.variants.iter().find(|v| v.tag.trim() == tag)
+                });
+            if let Some(variant) = variant {
+                fields.splice(i..i, variant.fields.clone().flatten());
+            }
+
+            let name = match field.name.clone() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            // The fields are written one after the other, whatever their declared ranges.
+            field.range = start..offset;
+            sums.push((name, read_sum(&bytes, &field, encoding)?));
+        }
+
+        self.write_record(&bytes)?;
+
+        let totals = match self.groups.last_mut() {
+            Some(group) => &mut group.totals,
+            None => &mut self.root,
+        };
+        totals.records += 1;
+
+        for (name, value) in sums {
+            totals.add_sum(name, value);
+        }
+
+        Ok(())
+    }
+
+    /// Closes the innermost open group by writing its trailer, and adds its totals to the group it
+    /// is nested in. Fails if no group is open.
+    pub fn close(&mut self) -> Result<()> {
+        let group = self
+            .groups
+            .pop()
+            .ok_or_else(|| SerializeError::Message("no open group to close".to_string()))?;
+
+        let bytes = (group.trailer)(&group.totals, self.wrtr.encoding)?;
+        self.write_record(&bytes)?;
+
+        match self.groups.last_mut() {
+            Some(parent) => parent.totals.add(&group.totals),
+            None => self.root.add(&group.totals),
+        }
+
+        Ok(())
+    }
+
+    /// Closes every open group and flushes the writer. Returns the totals of everything written.
+    pub fn finish(mut self) -> Result<Totals> {
+        while !self.groups.is_empty() {
+            self.close()?;
+        }
+
+        self.wrtr.flush()?;
+        Ok(self.root)
+    }
+
+    fn write_record(&mut self, bytes: &[u8]) -> Result<()> {
+        if !self.first_record {
+            self.wrtr.write_linebreak()?;
+        } else {
+            self.first_record = false;
+        }
+
+        self.wrtr.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+// Reads a field of a detail to add to its sum. Returns `None` for plain text that is not an
+// integer, which is left out of the sums.
+fn read_sum(record: &[u8], field: &FieldConfig, encoding: Encoding) -> Result<Sum> {
+    match de::read_integer(record, field, encoding) {
+        Ok(value) => Ok(Sum::Value(value.unwrap_or(0))),
+        Err(_) if field.codec == Codec::Text && field.sign == Sign::Inline => Ok(Sum::NotInteger),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn serialize<T: Serialize>(record: &T, fields: FieldSet, encoding: Encoding) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    record.serialize(&mut Serializer::new(&mut bytes, fields).encoding(encoding))?;
+    Ok(bytes)
 }
 
 impl<W> Write for Writer<W>
@@ -215,7 +572,7 @@ impl From<Writer<Vec<u8>>> for String {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{FieldSet, FixedWidth};
+    use crate::{FieldSet, FixedWidth, Overflow};
     use serde_derive::Serialize;
 
     #[test]
@@ -265,7 +622,10 @@ mod test {
 
     impl FixedWidth for Test2 {
         fn fields() -> FieldSet {
-            FieldSet::Seq(vec![FieldSet::new_field(0..3), FieldSet::new_field(3..6)])
+            FieldSet::Seq(vec![
+                FieldSet::new_field(0..3).overflow(Overflow::TruncateRight),
+                FieldSet::new_field(3..6),
+            ])
         }
     }
 
@@ -293,6 +653,273 @@ mod test {
         assert_eq!(s, "123foo\n12 fb \n123foo");
     }
 
+    #[derive(Serialize)]
+    struct Code(&'static str);
+
+    impl FixedWidth for Code {
+        fn fields() -> FieldSet {
+            FieldSet::new_field(0..1)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Entry {
+        routing: &'static str,
+        amount: i64,
+    }
+
+    impl FixedWidth for Entry {
+        fn fields() -> FieldSet {
+            FieldSet::Seq(vec![
+                FieldSet::new_field(0..4).name("routing"),
+                FieldSet::new_field(4..8)
+                    .name("amount")
+                    .pad_with('0')
+                    .justify("right"),
+            ])
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Control {
+        code: &'static str,
+        count: usize,
+        hash: i128,
+        total: i128,
+    }
+
+    impl FixedWidth for Control {
+        fn fields() -> FieldSet {
+            FieldSet::Seq(vec![
+                FieldSet::new_field(0..1),
+                FieldSet::new_field(1..3).pad_with('0').justify("right"),
+                FieldSet::new_field(3..6).pad_with('0').justify("right"),
+                FieldSet::new_field(6..11).pad_with('0').justify("right"),
+            ])
+        }
+    }
+
+    fn batch_control(t: &Totals) -> Result<Control> {
+        Ok(Control {
+            code: "8",
+            count: t.records(),
+            hash: t.hash("routing", 3)?,
+            total: t.sum("amount")?,
+        })
+    }
+
+    #[test]
+    fn totals_write() {
+        let mut w = Writer::from_memory().linebreak(LineBreak::Newline);
+        let mut totals = w.with_totals();
+
+        totals
+            .open(&Code("1"), |t| {
+                Ok(Control {
+                    code: "9",
+                    count: t.groups(),
+                    hash: t.hash("routing", 3)?,
+                    total: t.sum("amount")?,
+                })
+            })
+            .unwrap();
+        totals.open(&Code("5"), batch_control).unwrap();
+        totals
+            .write(&Entry {
+                routing: "0601",
+                amount: 12,
+            })
+            .unwrap();
+        totals
+            .write(&Entry {
+                routing: "0700",
+                amount: 30,
+            })
+            .unwrap();
+        totals.close().unwrap();
+        totals.open(&Code("5"), batch_control).unwrap();
+        totals
+            .write(&Entry {
+                routing: "0900",
+                amount: -2,
+            })
+            .unwrap();
+
+        let root = totals.finish().unwrap();
+        let s: String = w.into();
+
+        assert_eq!(
+            s,
+            "1\n5\n06010012\n07000030\n80230100042\n5\n090000-2\n801900000-2\n90220100040"
+        );
+        assert_eq!(root.records(), 3);
+        assert_eq!(root.groups(), 1);
+        assert_eq!(root.sum("amount").unwrap(), 40);
+    }
+
+    #[derive(Serialize)]
+    struct Payment {
+        payee: &'static str,
+        credit: i64,
+        debit: i64,
+    }
+
+    impl FixedWidth for Payment {
+        fn fields() -> FieldSet {
+            FieldSet::Seq(vec![
+                FieldSet::new_field(0..3).name("payee"),
+                FieldSet::new_field(3..6)
+                    .name("credit")
+                    .codec(Codec::Overpunch),
+                FieldSet::new_field(6..8)
+                    .name("debit")
+                    .codec(Codec::Packed { signed: true }),
+            ])
+        }
+    }
+
+    #[test]
+    fn totals_read_codecs() {
+        let mut w = Writer::from_memory();
+        let mut totals = w.with_totals();
+
+        for (payee, credit, debit) in [("ABC", 12, 7), ("123", -30, -105), ("DEF", 5, 0)] {
+            totals
+                .write(&Payment {
+                    payee,
+                    credit,
+                    debit,
+                })
+                .unwrap();
+        }
+
+        let root = totals.finish().unwrap();
+
+        assert_eq!(root.records(), 3);
+        assert_eq!(root.sum("credit").unwrap(), -13);
+        assert_eq!(root.sum("debit").unwrap(), -98);
+        assert!(root.sum("payee").is_err());
+    }
+
+    #[test]
+    fn totals_reject_unreadable_numbers() {
+        #[derive(Serialize)]
+        struct Amount(&'static str);
+
+        impl FixedWidth for Amount {
+            fn fields() -> FieldSet {
+                FieldSet::new_field(0..4)
+                    .name("amount")
+                    .sign(Sign::Trailing)
+            }
+        }
+
+        let mut w = Writer::from_memory();
+        let mut totals = w.with_totals();
+
+        totals.write(&Amount("12")).unwrap();
+        assert!(totals.write(&Amount("1A")).is_err());
+
+        let root = totals.finish().unwrap();
+        let s: String = w.into();
+
+        assert_eq!(s, "12  ");
+        assert_eq!(root.sum("amount").unwrap(), 12);
+    }
+
+    #[test]
+    fn totals_sum_variant_fields() {
+        #[derive(Serialize)]
+        enum Record {
+            Detail { name: &'static str, amount: u32 },
+            Note { text: &'static str },
+        }
+
+        impl FixedWidth for Record {
+            fn fields() -> FieldSet {
+                FieldSet::new_field(0..1)
+                    .variant(
+                        "D",
+                        "Detail",
+                        FieldSet::Seq(vec![
+                            FieldSet::new_field(1..5),
+                            FieldSet::new_field(5..9).name("amount"),
+                        ]),
+                    )
+                    .variant(
+                        "N",
+                        "Note",
+                        FieldSet::Seq(vec![FieldSet::new_field(1..9).name("text")]),
+                    )
+            }
+        }
+
+        let mut w = Writer::from_memory();
+        let mut totals = w.with_totals();
+
+        totals
+            .write(&Record::Detail {
+                name: "Ann",
+                amount: 12,
+            })
+            .unwrap();
+        totals.write(&Record::Note { text: "12345678" }).unwrap();
+        totals
+            .write(&Record::Detail {
+                name: "Bob",
+                amount: 30,
+            })
+            .unwrap();
+
+        let root = totals.finish().unwrap();
+
+        assert_eq!(root.records(), 3);
+        assert_eq!(root.sum("amount").unwrap(), 42);
+        assert_eq!(root.sum("text").unwrap(), 12345678);
+    }
+
+    #[test]
+    fn totals_fail_instead_of_guessing() {
+        #[derive(Serialize)]
+        struct Amount(f64);
+
+        impl FixedWidth for Amount {
+            fn fields() -> FieldSet {
+                FieldSet::new_field(0..5).name("amount")
+            }
+        }
+
+        let mut w = Writer::from_memory();
+        let mut totals = w.with_totals();
+
+        totals
+            .open(&Code("5"), |t| {
+                Ok(Control {
+                    code: "8",
+                    count: t.records(),
+                    hash: 0,
+                    total: t.sum("amount")?,
+                })
+            })
+            .unwrap();
+        totals.write(&Amount(12.5)).unwrap();
+        assert!(totals.close().is_err());
+
+        let mut big = Totals::default();
+        big.add_sum("amount".to_string(), Sum::Value(i128::MAX));
+        assert_eq!(big.hash("amount", 38).unwrap(), i128::MAX % 10i128.pow(38));
+        assert!(big.hash("amount", 39).is_err());
+
+        big.add_sum("amount".to_string(), Sum::Value(1));
+        assert!(big.sum("amount").is_err());
+    }
+
+    #[test]
+    fn close_without_group() {
+        let mut w = Writer::from_memory();
+        assert!(w.with_totals().close().is_err());
+    }
+
     #[test]
     fn test_write() {
         let bytes = b"abcd1234";
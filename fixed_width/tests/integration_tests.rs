@@ -85,3 +85,41 @@ fn write_to_file_with_newlines() {
     fs::remove_file(path).unwrap();
     assert_eq!(expected, s);
 }
+
+#[cfg(feature = "schema")]
+#[test]
+fn read_with_schema_file() {
+    use fixed_width::{schema::Schema, FieldSet};
+    use std::collections::HashMap;
+
+    let path = "./tests/data/sample_schema.toml";
+    let fields = FieldSet::builder()
+        .field("a", 4)
+        .field("b", 4)
+        .field("c", 8)
+        .build();
+
+    let mut schema = Schema::from_field_set(&fields);
+    schema.linebreak = Some(LineBreak::Newline);
+    schema.to_file(path).unwrap();
+
+    let schema = Schema::from_file(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    let fields = schema.field_set().unwrap();
+    let mut rdr = schema
+        .reader(File::open("./tests/data/sample_file_newlines.txt").unwrap())
+        .unwrap();
+
+    let rows = rdr
+        .byte_reader()
+        .filter_map(result::Result::ok)
+        .map(|r| fixed_width::from_bytes_with_fields(&r, fields.clone()).unwrap())
+        .collect::<Vec<HashMap<String, String>>>();
+
+    assert_eq!(rows.len(), 3);
+
+    for row in rows {
+        assert_eq!(row.get("c").unwrap(), "33334444");
+    }
+}
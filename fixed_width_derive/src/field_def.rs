@@ -3,11 +3,15 @@ use syn::LitStr;
 
 pub struct Container {
     pub fixed_width_fn: Option<syn::Ident>,
+    pub strict: bool,
+    pub tag: Option<String>,
 }
 
 impl Container {
     pub fn from_ast(ast: &syn::DeriveInput) -> Self {
         let mut fixed_width_fn: Option<syn::Ident> = None;
+        let mut strict = false;
+        let mut tag = None;
 
         for attr in &ast.attrs {
             if attr.path().is_ident("fixed_width") {
@@ -21,13 +25,23 @@ impl Container {
                         } else {
                             fixed_width_fn = Some(syn::Ident::new(&fixed_width_fn_name.value(), proc_macro2::Span::call_site()));
                         }
+                    } else if meta.path.is_ident("strict") {
+                        strict = true;
+                    } else if meta.path.is_ident("tag") {
+                        let value = meta.value().expect("expected to find a byte range, ie fixed_width(tag = \"0..1\")");
+                        let range: LitStr = value.parse().expect("expected to find a byte range, ie fixed_width(tag = \"0..1\")");
+                        tag = Some(range.value());
                     }
                     Ok(())
                 }).expect("expected fixed_width(...)");
             }
         }
 
-        Self { fixed_width_fn }
+        Self {
+            fixed_width_fn,
+            strict,
+            tag,
+        }
     }
 }
 
@@ -40,6 +54,20 @@ pub struct FieldDef {
     pub pad_with: char,
     pub range: Range<usize>,
     pub justify: String,
+    pub trim: String,
+    pub overflow: Option<String>,
+    pub scale: usize,
+    pub sign: String,
+    pub format: Option<String>,
+    pub booleans: Option<(Vec<String>, Vec<String>)>,
+    pub null: Option<Null>,
+}
+
+#[derive(Debug)]
+pub enum Null {
+    Text(String),
+    Fill(char),
+    Byte(u8),
 }
 
 pub struct Context {
@@ -117,6 +145,31 @@ impl Context {
     }
 }
 
+/// The value of the tag field that selects a variant of a tagged enum.
+pub fn tag_value(variant: &syn::Variant) -> String {
+    let mut tag_value = None;
+
+    for attr in &variant.attrs {
+        if attr.path().is_ident("fixed_width") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag_value") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    tag_value = Some(s.value());
+                }
+                Ok(())
+            })
+            .unwrap_or_else(|_| {
+                panic!(
+                    "could not parse fixed_width metadata for variant: {}",
+                    variant.ident
+                )
+            });
+        }
+    }
+
+    tag_value.unwrap_or_else(|| panic!("Must supply a tag_value for variant: {}", variant.ident))
+}
+
 #[allow(dead_code)]
 pub struct Metadata {
     pub name: String,
@@ -3,7 +3,7 @@ This create provides a derive macro for the `fixed_width` crate's `FixedWidth` t
 a set of struct container/field [attributes](https://doc.rust-lang.org/book/attributes.html)
 that can be used to more easily derive the trait.
 
-The derive only works on structs. Additionally, this crate uses features that require Rust version 1.30.0+ to run.
+The derive works on structs, and on enums whose variant is selected by a tag field. Additionally, this crate uses features that require Rust version 1.30.0+ to run.
 
 # Installing
 
@@ -85,7 +85,7 @@ impl FixedWidth for Person {
 
 There are two categories of attributes:
 
-- Container attribure - apply to a struct.
+- Container attribure - apply to a struct or an enum.
 - Field attributes - apply to a filed in a struct.
 
 ## Container attributes
@@ -95,30 +95,116 @@ There are two categories of attributes:
 Call a function to get the fields definition. The given function must be callable
 as `fn() -> fixed_width::FieldSet`.
 
+- `strict`
+
+Fail to compile if any byte of the record is not covered by a field. Overlapping or inverted
+ranges, fields defined out of order and duplicate names always fail to compile.
+
+- `tag = "x..y"`
+
+Required on enums. The byte range holding the tag of each record, which selects the variant it is read
+into. Each variant gives its tag with `tag_value = "s"`, and is either a struct variant laid out like a struct,
+whose fields start after the tag, a newtype variant of a `FixedWidth` type that does not include the tag, or
+a unit variant. The tag is written first, followed by the fields of the variant.
+
+```rust
+use serde_derive::{Deserialize, Serialize};
+use fixed_width_derive::FixedWidth;
+
+#[derive(FixedWidth, Deserialize, Serialize)]
+#[fixed_width(tag = "0..1")]
+enum FileRecord {
+    #[fixed_width(tag_value = "H")]
+    Header {
+        #[fixed_width(width = "8")]
+        date: String,
+    },
+    #[fixed_width(tag_value = "D")]
+    Detail {
+        #[fixed_width(width = "4")]
+        name: String,
+        #[fixed_width(width = "4", pad_with = "0", justify = "right")]
+        amount: u32,
+    },
+}
+
+let record: FileRecord = fixed_width::from_bytes(b"DAnn 0012").unwrap();
+assert!(matches!(record, FileRecord::Detail { amount: 12, .. }));
+```
+
 ## Field attributes
 
 The full set of options you can supply for the attribute annotations are:
 
 - `range = "x..y"`
 
-Required. Range values must be of type `usize`. The byte range of the given field.
+Range values must be of type `usize`. The byte range of the given field. Either `range` or
+`width` is required.
+
+- `width = "n"`
+
+Must be of type `usize`. The width in bytes of the given field. The field starts where the
+previous field ends, or at byte 0 for the first field, so inserting a field does not require
+renumbering the ranges after it. `width` and `range` can be mixed within a struct.
 
 - `pad_with = "c"`
 
 Defaults to `' '`. Must be of type `char`. The character to pad to the left or right after the
 value of the field has been converted to bytes. For instance, if the width of
 the field was 5, and the value is `"foo"`, then a left justified field padded with `a`
-results in: `"fooaa"`.
+results in: `"fooaa"`. The character is written in the encoding of the record, and may take more than
+one byte.
 
 - `justify = "left|right"`
 
 Defaults to `"left"`. Must be of enum type `Justify`. Indicates whether this field should be justified
 left or right once it has been converted to bytes.
 
+- `trim = "justified|pad|whitespace|raw"`
+
+Defaults to `"justified"`. Must be of enum type `Trim`. Indicates how the padding of this field is removed
+when it is read: the pad character on the padded side only, the pad character on both sides, whitespace
+on both sides, or nothing at all.
+
+- `overflow = "error|truncate_right|truncate_left|ellipsis"`
+
+Must be of enum type `Overflow`. Indicates what to do with a value that is longer than this field when it is
+written. Defaults to the policy of the `Serializer`: an error for numbers, and truncating the end of any
+other value.
+
 - `name = "s"`
 
 Defaults to the name of the struct field. Indicates the name of the field. Useful if you wish to deserialize
 fixed width data into a HashMap.
+
+- `scale = "n"`
+
+Defaults to `"0"`. Must be of type `usize`. The number of digits after the implied decimal point of a
+numeric field, so that `0001234567` with a scale of `2` is read as `12345.67`. Values that would have
+to be rounded fail to serialize or deserialize.
+
+- `sign = "inline|leading|trailing|separate|always"`
+
+Defaults to `"inline"`. Must be of enum type `Sign`. Indicates where the sign of a signed number is written,
+so that `-12` in a 5 byte field padded with `0` is written as `-0012` with `"leading"` rather than `00-12`.
+
+- `bool_true = "Y,y"`, `bool_false = "N,n"` and `bool_blank = "true|false"`
+
+Must be given together, except for `bool_blank`. The comma separated values of a boolean field read as `true`
+and `false`, see `Booleans`. The first value of each list is the one written. `bool_blank` reads a blank field
+as the given value, and other values fail to deserialize. Without them, `1` and `0` are written and any value
+other than blank or `0` is read as `true`.
+
+- `null = "NULL"`, `null_fill = "c"` or `null_byte = "0x00"`
+
+At most one can be given. How a missing value is represented in the field, see `Null`: the given text, the
+field filled with the given character, or the field filled with the given byte, written in decimal or in
+hexadecimal with a `0x` prefix. Fields holding it are read as `None`, and `None` is written as it.
+
+- `format = "%Y%m%d"`
+
+Requires the `chrono` feature of `fixed_width`. The date format of the field, see `FieldSet::format`. Dates
+and times are read into and written from `chrono` types, and blank or all zero dates are read as `None`.
 */
 
 extern crate proc_macro;
@@ -126,7 +212,7 @@ extern crate proc_macro2;
 #[macro_use]
 extern crate quote;
 
-use crate::field_def::{Container, Context, FieldDef};
+use crate::field_def::{Container, Context, FieldDef, Null};
 use proc_macro::TokenStream;
 use std::result;
 use syn::DeriveInput;
@@ -140,6 +226,10 @@ pub fn fixed_width(input: TokenStream) -> TokenStream {
 }
 
 fn impl_fixed_width(ast: &DeriveInput) -> TokenStream {
+    if let syn::Data::Enum(ref data) = ast.data {
+        return impl_tagged_enum(ast, data);
+    }
+
     let fields: Vec<syn::Field> = match ast.data {
         syn::Data::Struct(syn::DataStruct { ref fields, .. }) => {
             if fields.iter().any(|field| field.ident.is_none()) {
@@ -147,7 +237,7 @@ fn impl_fixed_width(ast: &DeriveInput) -> TokenStream {
             }
             fields.iter().cloned().collect()
         }
-        _ => panic!("#[derive(FixedWidth)] can only be used with structs"),
+        _ => panic!("#[derive(FixedWidth)] can only be used with structs and enums"),
     };
 
     let ident = &ast.ident;
@@ -155,9 +245,7 @@ fn impl_fixed_width(ast: &DeriveInput) -> TokenStream {
 
     let container = Container::from_ast(ast);
 
-    if container.fixed_width_fn.is_some() {
-        let field_def = container.fixed_width_fn.unwrap();
-
+    if let Some(field_def) = container.fixed_width_fn {
         for field in &fields {
             for attr in &field.attrs {
                 if attr.path().is_ident("fixed_width") {
@@ -176,10 +264,17 @@ fn impl_fixed_width(ast: &DeriveInput) -> TokenStream {
 
         quote.into()
     } else {
-        let tokens: Vec<proc_macro2::TokenStream> = fields
+        let mut offset = 0;
+        let field_defs: Vec<FieldDef> = fields
             .iter()
             .filter(should_skip)
-            .map(build_field_def)
+            .map(|field| build_field_def(field, &mut offset))
+            .collect();
+
+        validate_layout(&field_defs, container.strict, 0);
+
+        let tokens: Vec<proc_macro2::TokenStream> = field_defs
+            .into_iter()
             .map(build_fixed_width_field)
             .collect();
 
@@ -195,11 +290,107 @@ fn impl_fixed_width(ast: &DeriveInput) -> TokenStream {
     }
 }
 
+fn impl_tagged_enum(ast: &DeriveInput, data: &syn::DataEnum) -> TokenStream {
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let container = Container::from_ast(ast);
+    let tag = container.tag.clone().unwrap_or_else(|| {
+        panic!(
+            "Must supply a tag range for enum: {}, ie fixed_width(tag = \"0..1\")",
+            ident
+        )
+    });
+    let tag_range = parse_range(&tag).unwrap_or_else(|| panic!("Invalid tag range {}", tag));
+
+    let mut tag_values: Vec<String> = vec![];
+    let variants: Vec<proc_macro2::TokenStream> = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let tag_value = field_def::tag_value(variant);
+            if tag_values.contains(&tag_value) {
+                panic!(
+                    "Tag value `{}` is used by more than one variant of enum: {}",
+                    tag_value, ident
+                );
+            }
+            tag_values.push(tag_value.clone());
+
+            let name = variant.ident.to_string();
+            let fields = match variant.fields {
+                syn::Fields::Named(ref named) => {
+                    let mut offset = tag_range.end;
+                    let field_defs: Vec<FieldDef> = named
+                        .named
+                        .iter()
+                        .filter(should_skip)
+                        .map(|field| build_field_def(field, &mut offset))
+                        .collect();
+
+                    for def in &field_defs {
+                        if def.range.start < tag_range.end && tag_range.start < def.range.end {
+                            panic!(
+                                "Range {}..{} for field: {} overlaps the tag of variant: {}",
+                                def.range.start, def.range.end, def.ident, variant.ident
+                            );
+                        }
+                    }
+                    validate_layout(&field_defs, container.strict, tag_range.end);
+
+                    let tokens: Vec<proc_macro2::TokenStream> = field_defs
+                        .into_iter()
+                        .map(build_fixed_width_field)
+                        .collect();
+                    quote! { fixed_width::FieldSet::Seq(vec![#(#tokens),*]) }
+                }
+                syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+                    let ty = &unnamed.unnamed[0].ty;
+                    quote! { <#ty as fixed_width::FixedWidth>::fields() }
+                }
+                syn::Fields::Unnamed(_) => panic!(
+                    "Variant: {} must have named fields or a single field of a FixedWidth type",
+                    variant.ident
+                ),
+                syn::Fields::Unit => quote! { fixed_width::FieldSet::Seq(vec![]) },
+            };
+
+            quote! { .variant(#tag_value, #name, #fields) }
+        })
+        .collect();
+
+    let start = tag_range.start;
+    let end = tag_range.end;
+    let quote = quote! {
+        impl #impl_generics fixed_width::FixedWidth for #ident #ty_generics #where_clause {
+            fn fields() -> fixed_width::FieldSet {
+                fixed_width::FieldSet::new_field(#start..#end)
+                    #(#variants)*
+            }
+        }
+    };
+
+    quote.into()
+}
+
+fn parse_range(s: &str) -> Option<std::ops::Range<usize>> {
+    let range_parts = s
+        .split("..")
+        .map(str::parse)
+        .filter_map(result::Result::ok)
+        .collect::<Vec<usize>>();
+
+    match range_parts[..] {
+        [start, end] => Some(start..end),
+        _ => None,
+    }
+}
+
 fn should_skip(field: &&syn::Field) -> bool {
     !Context::from_field(field).skip
 }
 
-fn build_field_def(field: &syn::Field) -> FieldDef {
+fn build_field_def(field: &syn::Field, offset: &mut usize) -> FieldDef {
     let ctx = Context::from_field(field);
 
     let name = match ctx.metadata.get("name") {
@@ -207,25 +398,30 @@ fn build_field_def(field: &syn::Field) -> FieldDef {
         None => ctx.field_name(),
     };
 
-    let range = if let Some(r) = ctx.metadata.get("range") {
-        let range_parts = r
-            .value
-            .split("..")
-            .map(str::parse)
-            .filter_map(result::Result::ok)
-            .collect::<Vec<usize>>();
-
-        if range_parts.len() != 2 {
-            panic!("Invalid range {} for field: {}", r.value, ctx.field_name());
+    let range = match (ctx.metadata.get("range"), ctx.metadata.get("width")) {
+        (Some(_), Some(_)) => panic!(
+            "Specify either a range or a width for field: {}",
+            ctx.field_name()
+        ),
+        (Some(r), None) => parse_range(&r.value)
+            .unwrap_or_else(|| panic!("Invalid range {} for field: {}", r.value, ctx.field_name())),
+        (None, Some(w)) => {
+            let width: usize = w.value.trim().parse().unwrap_or_else(|_| {
+                panic!("Invalid width {} for field: {}", w.value, ctx.field_name())
+            });
+
+            *offset..*offset + width
         }
-
-        range_parts[0]..range_parts[1]
-    } else {
-        panic!("Must supply a byte range for field: {}", ctx.field_name());
+        (None, None) => panic!(
+            "Must supply a byte range or width for field: {}",
+            ctx.field_name()
+        ),
     };
 
+    *offset = range.end;
+
     let pad_with = ctx.metadata.get("pad_with").map_or(' ', |c| {
-        if c.value.len() != 1 {
+        if c.value.chars().count() != 1 {
             panic!("pad_with must be a char for field: {}", ctx.field_name());
         }
 
@@ -243,6 +439,111 @@ fn build_field_def(field: &syn::Field) -> FieldDef {
         None => "left".to_string(),
     };
 
+    let trim = match ctx.metadata.get("trim") {
+        Some(t) => match t.value.to_lowercase().trim() {
+            "justified" | "pad" | "whitespace" | "raw" => t.value.clone(),
+            _ => panic!(
+                "trim must be 'justified', 'pad', 'whitespace' or 'raw' for field: {}",
+                ctx.field_name()
+            ),
+        },
+        None => "justified".to_string(),
+    };
+
+    let overflow = ctx.metadata.get("overflow").map(|o| {
+        match o.value.to_lowercase().trim() {
+            "error" | "truncate_right" | "truncate_left" | "ellipsis" => o.value.clone(),
+            _ => panic!(
+                "overflow must be 'error', 'truncate_right', 'truncate_left' or 'ellipsis' for field: {}",
+                ctx.field_name()
+            ),
+        }
+    });
+
+    let scale: usize = ctx.metadata.get("scale").map_or(0, |s| {
+        s.value
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid scale {} for field: {}", s.value, ctx.field_name()))
+    });
+
+    let sign = match ctx.metadata.get("sign") {
+        Some(s) => match s.value.to_lowercase().trim() {
+            "inline" | "leading" | "trailing" | "separate" | "always" => s.value.clone(),
+            _ => panic!(
+                "sign must be 'inline', 'leading', 'trailing', 'separate' or 'always' for field: {}",
+                ctx.field_name()
+            ),
+        },
+        None => "inline".to_string(),
+    };
+
+    let values = |key: &str| {
+        ctx.metadata
+            .get(key)
+            .map(|m| m.value.split(',').map(str::to_string).collect::<Vec<_>>())
+    };
+
+    let booleans = match (values("bool_true"), values("bool_false")) {
+        (Some(mut t), Some(mut f)) => {
+            match ctx.metadata.get("bool_blank").map(|b| b.value.trim()) {
+                Some("true") => t.push(String::new()),
+                Some("false") => f.push(String::new()),
+                Some(_) => panic!(
+                    "bool_blank must be 'true' or 'false' for field: {}",
+                    ctx.field_name()
+                ),
+                None => {}
+            }
+            Some((t, f))
+        }
+        (None, None) if ctx.metadata.contains_key("bool_blank") => panic!(
+            "bool_blank requires bool_true and bool_false for field: {}",
+            ctx.field_name()
+        ),
+        (None, None) => None,
+        _ => panic!(
+            "bool_true and bool_false must be given together for field: {}",
+            ctx.field_name()
+        ),
+    };
+
+    let null = match (
+        ctx.metadata.get("null"),
+        ctx.metadata.get("null_fill"),
+        ctx.metadata.get("null_byte"),
+    ) {
+        (None, None, None) => None,
+        (Some(t), None, None) => Some(Null::Text(t.value.clone())),
+        (None, Some(f), None) => {
+            if f.value.chars().count() != 1 {
+                panic!("null_fill must be a char for field: {}", ctx.field_name());
+            }
+            Some(Null::Fill(f.value.chars().next().unwrap()))
+        }
+        (None, None, Some(b)) => {
+            let value = b.value.trim();
+            let byte = match value
+                .strip_prefix("0x")
+                .or_else(|| value.strip_prefix("0X"))
+            {
+                Some(hex) => u8::from_str_radix(hex, 16),
+                None => value.parse(),
+            };
+            Some(Null::Byte(byte.unwrap_or_else(|_| {
+                panic!(
+                    "Invalid null_byte {} for field: {}",
+                    b.value,
+                    ctx.field_name()
+                )
+            })))
+        }
+        _ => panic!(
+            "Only one of null, null_fill and null_byte can be given for field: {}",
+            ctx.field_name()
+        ),
+    };
+
     FieldDef {
         ident: ctx.field.ident.unwrap(),
         field_type: field.ty.clone(),
@@ -250,6 +551,73 @@ fn build_field_def(field: &syn::Field) -> FieldDef {
         pad_with,
         range,
         justify,
+        trim,
+        overflow,
+        scale,
+        sign,
+        format: ctx.metadata.get("format").map(|f| f.value.clone()),
+        booleans,
+        null,
+    }
+}
+
+fn validate_layout(field_defs: &[FieldDef], strict: bool, start: usize) {
+    let mut previous: Option<&FieldDef> = None;
+
+    for (i, def) in field_defs.iter().enumerate() {
+        if def.range.end < def.range.start {
+            panic!(
+                "Inverted range {}..{} for field: {}",
+                def.range.start, def.range.end, def.ident
+            );
+        }
+
+        if let Some(prev) = previous {
+            if def.range.start < prev.range.start {
+                panic!(
+                    "Field: {} starts before the previous field: {}",
+                    def.ident, prev.ident
+                );
+            }
+        }
+
+        if def.range.start < def.range.end {
+            previous = Some(def);
+        }
+
+        for other in &field_defs[i + 1..] {
+            if def.range.start < other.range.end && other.range.start < def.range.end {
+                panic!(
+                    "Range {}..{} for field: {} overlaps range {}..{} for field: {}",
+                    def.range.start,
+                    def.range.end,
+                    def.ident,
+                    other.range.start,
+                    other.range.end,
+                    other.ident
+                );
+            }
+
+            if def.name == other.name {
+                panic!(
+                    "Name `{}` is used by fields: {} and {}",
+                    def.name, def.ident, other.ident
+                );
+            }
+        }
+    }
+
+    if strict {
+        let mut covered = start;
+        for def in field_defs.iter().filter(|d| d.range.start < d.range.end) {
+            if def.range.start > covered {
+                panic!(
+                    "Bytes {}..{} are not covered by any field",
+                    covered, def.range.start
+                );
+            }
+            covered = def.range.end;
+        }
     }
 }
 
@@ -259,11 +627,38 @@ fn build_fixed_width_field(field_def: FieldDef) -> proc_macro2::TokenStream {
     let end = field_def.range.end;
     let pad_with = field_def.pad_with;
     let justify = field_def.justify;
+    let trim = field_def.trim;
+    let overflow = field_def
+        .overflow
+        .map(|o| quote! { .overflow(#o.to_string()) });
+    let scale = field_def.scale;
+    let sign = field_def.sign;
+    let booleans = field_def.booleans.map(|(t, f)| {
+        quote! {
+            .booleans(fixed_width::Booleans {
+                true_values: vec![#(#t.to_string()),*],
+                false_values: vec![#(#f.to_string()),*],
+            })
+        }
+    });
+    let null = field_def.null.map(|null| match null {
+        Null::Text(t) => quote! { .null(fixed_width::Null::Text(#t.to_string())) },
+        Null::Fill(c) => quote! { .null(fixed_width::Null::Fill(#c)) },
+        Null::Byte(b) => quote! { .null(fixed_width::Null::Byte(#b)) },
+    });
+    let format = field_def.format.map(|f| quote! { .format(#f) });
 
     quote! {
         fixed_width::FieldSet::new_field(#start..#end)
             .name(#name)
             .pad_with(#pad_with)
             .justify(#justify.to_string())
+            .trim(#trim.to_string())
+            .scale(#scale)
+            .sign(#sign.to_string())
+            #format
+            #booleans
+            #null
+            #overflow
     }
 }
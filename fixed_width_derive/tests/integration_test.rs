@@ -67,6 +67,32 @@ struct SkippedStuff {
     pub stuff6: String,
 }
 
+#[derive(FixedWidth, Deserialize)]
+#[fixed_width(strict)]
+#[allow(dead_code)]
+struct StrictStuff {
+    #[fixed_width(range = "0..6")]
+    pub stuff1: String,
+    #[fixed_width(range = "6..9")]
+    pub stuff2: usize,
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct ByWidth {
+    #[fixed_width(width = "6")]
+    pub stuff1: String,
+    #[fixed_width(width = "6", pad_with = "0")]
+    pub stuff2: String,
+    #[fixed_width(width = "3", pad_with = "0")]
+    pub stuff3: usize,
+    #[fixed_width(width = "4")]
+    pub stuff4: usize,
+    #[fixed_width(range = "21..27")]
+    pub stuff5: String,
+    #[fixed_width(width = "4", justify = "right")]
+    pub stuff6: String,
+}
+
 fn field_def_fields() -> fixed_width::FieldSet {
     fixed_width::FieldSet::Seq(vec![
         fixed_width::FieldSet::new_field(0..3),
@@ -108,12 +134,12 @@ fn test_deserialize() {
     let mut de = Deserializer::new(fr, Stuff::fields());
     let stuff = Stuff::deserialize(&mut de).unwrap();
 
-    assert_eq!(stuff.stuff1, "foo");
+    assert_eq!(stuff.stuff1, "   foo");
     assert_eq!(stuff.stuff2, "000bar");
     assert_eq!(stuff.stuff3, 234);
     assert_eq!(stuff.stuff4, 9);
     assert_eq!(stuff.stuff5, "foobar");
-    assert_eq!(stuff.stuff6, "123");
+    assert_eq!(stuff.stuff6, "123 ");
 }
 
 #[test]
@@ -124,7 +150,7 @@ fn test_deserialize_multiple() {
 
     for record in rdr.byte_reader().filter_map(result::Result::ok) {
         let stuff: Stuff = fixed_width::from_bytes(&record).unwrap();
-        assert_eq!(stuff.stuff1, "foo");
+        assert_eq!(stuff.stuff1, "   foo");
         assert_eq!(stuff.stuff2, "000bar");
     }
 }
@@ -137,7 +163,12 @@ fn test_from_fixed_record_when_input_is_too_small() {
 
     match err {
         Ok(_) => panic!("expected Err, got Ok"),
-        Err(DeserializeError::UnexpectedEndOfRecord) => {}
+        Err(DeserializeError::Field {
+            name, range, error, ..
+        }) if matches!(*error, DeserializeError::UnexpectedEndOfRecord) => {
+            assert_eq!(name.as_deref(), Some("stuff5"));
+            assert_eq!(range, 21..27);
+        }
         Err(e) => panic!("expected InvalidRecordError, got {}", e),
     }
 }
@@ -188,7 +219,7 @@ fn test_multiple_record_types() {
             Some(b'1') => {
                 let Record2 { name, .. } = fixed_width::from_bytes(bytes).unwrap();
                 rec2 = true;
-                assert_eq!(name, "BOB");
+                assert_eq!(name, " BOB");
             }
             Some(_) => panic!("unexpected record type"),
             None => panic!("unexpected None"),
@@ -204,12 +235,12 @@ fn test_deserialize_with_skipped_fields() {
     let mut de = Deserializer::new(fr, SkippedStuff::fields());
     let stuff = SkippedStuff::deserialize(&mut de).unwrap();
 
-    assert_eq!(stuff.stuff1, "foo");
+    assert_eq!(stuff.stuff1, "   foo");
     assert_eq!(stuff.stuff2, "000bar");
     assert_eq!(stuff.stuff3, 234);
     assert_eq!(stuff.stuff4, 9);
     assert_eq!(stuff.stuff5, "foobar");
-    assert_eq!(stuff.stuff6, "123");
+    assert_eq!(stuff.stuff6, "123 ");
 }
 
 #[test]
@@ -220,3 +251,328 @@ fn test_specify_fields_by_field_def() {
     assert_eq!(data.id, 999);
     assert_eq!(data.name, "foobar");
 }
+
+#[test]
+fn test_derived_layout_is_valid() {
+    let report = Stuff::fields().validate();
+
+    assert!(report.is_valid());
+    assert_eq!(report.width(), 31);
+
+    let report = StrictStuff::fields().validate_strict();
+
+    assert!(report.is_valid());
+    assert_eq!(report.width(), 9);
+}
+
+#[test]
+fn test_width_matches_range() {
+    assert_eq!(
+        format!("{:?}", ByWidth::fields()),
+        format!("{:?}", Stuff::fields())
+    );
+
+    let fr = "   foo000bar234   9  foobar123 ".as_bytes();
+    let stuff: ByWidth = fixed_width::from_bytes(fr).unwrap();
+
+    assert_eq!(stuff.stuff4, 9);
+    assert_eq!(stuff.stuff6, "123 ");
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Amounts {
+    #[fixed_width(width = "10", scale = "2", justify = "right", pad_with = "0")]
+    pub amount: f64,
+    #[fixed_width(width = "6", scale = "3", justify = "right", pad_with = "0")]
+    pub whole: u32,
+}
+
+#[test]
+fn test_implied_decimals() {
+    let amounts: Amounts = fixed_width::from_str("0001234567042000").unwrap();
+
+    assert_eq!(amounts.amount, 12345.67);
+    assert_eq!(amounts.whole, 42);
+    assert_eq!(
+        fixed_width::to_string(&amounts).unwrap(),
+        "0001234567042000"
+    );
+
+    assert!(fixed_width::from_str::<Amounts>("0001234567042001").is_err());
+
+    let inexact = Amounts {
+        amount: 0.125,
+        whole: 0,
+    };
+    assert!(fixed_width::to_string(&inexact).is_err());
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Changes {
+    #[fixed_width(width = "5", justify = "right", pad_with = "0", sign = "leading")]
+    pub leading: i32,
+    #[fixed_width(width = "5", justify = "right", pad_with = "0", sign = "trailing")]
+    pub trailing: i32,
+}
+
+#[test]
+fn test_sign_placement() {
+    let changes: Changes = fixed_width::from_str("-00120012-").unwrap();
+
+    assert_eq!((changes.leading, changes.trailing), (-12, -12));
+    assert_eq!(fixed_width::to_string(&changes).unwrap(), "-00120012-");
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Dates {
+    #[fixed_width(width = "8", format = "%Y%m%d")]
+    pub opened: chrono::NaiveDate,
+    #[fixed_width(width = "5", format = "%y%j")]
+    pub closed: Option<chrono::NaiveDate>,
+}
+
+#[test]
+fn test_date_formats() {
+    let dates: Dates = fixed_width::from_str("20240131     ").unwrap();
+
+    assert_eq!(
+        dates.opened,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+    );
+    assert_eq!(dates.closed, None);
+
+    let dates = Dates {
+        closed: chrono::NaiveDate::from_ymd_opt(2024, 2, 29),
+        ..dates
+    };
+    assert_eq!(fixed_width::to_string(&dates).unwrap(), "2024013124060");
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Flags {
+    #[fixed_width(width = "1", bool_true = "Y,y", bool_false = "N,n")]
+    pub active: bool,
+    #[fixed_width(width = "1", bool_true = "X", bool_false = "", bool_blank = "false")]
+    pub deleted: bool,
+}
+
+#[test]
+fn test_boolean_mappings() {
+    let flags: Flags = fixed_width::from_str("y ").unwrap();
+
+    assert!(flags.active);
+    assert!(!flags.deleted);
+    assert_eq!(fixed_width::to_string(&flags).unwrap(), "Y ");
+
+    assert!(fixed_width::from_str::<Flags>("1 ").is_err());
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Nulls {
+    #[fixed_width(width = "4", null_fill = "9")]
+    pub count: Option<u32>,
+    #[fixed_width(width = "4", null = "NULL")]
+    pub name: Option<String>,
+    #[fixed_width(width = "2", null_byte = "0x00")]
+    pub code: Option<u16>,
+    #[fixed_width(width = "8", format = "%Y%m%d", null_fill = "0")]
+    pub closed: Option<chrono::NaiveDate>,
+}
+
+#[test]
+fn test_null_representations() {
+    let input = b"9999NULL\x00\x0000000000";
+    let nulls: Nulls = fixed_width::from_bytes(input).unwrap();
+
+    assert_eq!(nulls.count, None);
+    assert_eq!(nulls.name, None);
+    assert_eq!(nulls.code, None);
+    assert_eq!(nulls.closed, None);
+    assert_eq!(fixed_width::to_bytes(&nulls).unwrap(), input.to_vec());
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Trimmed {
+    #[fixed_width(width = "6")]
+    pub indented: String,
+    #[fixed_width(width = "6", pad_with = "0")]
+    pub code: String,
+    #[fixed_width(width = "4", pad_with = "0", justify = "right")]
+    pub count: u32,
+    #[fixed_width(width = "6", trim = "whitespace")]
+    pub loose: String,
+    #[fixed_width(width = "4", trim = "raw")]
+    pub raw: String,
+}
+
+#[test]
+fn test_trimming() {
+    let input = "  abc 1200000012  bar  x  ";
+    let trimmed: Trimmed = fixed_width::from_str(input).unwrap();
+
+    assert_eq!(trimmed.indented, "  abc");
+    assert_eq!(trimmed.code, "12");
+    assert_eq!(trimmed.count, 12);
+    assert_eq!(trimmed.loose, "bar");
+    assert_eq!(trimmed.raw, " x  ");
+
+    let zeros: Trimmed = fixed_width::from_str("      0000000000      0000").unwrap();
+    assert_eq!(zeros.count, 0);
+    assert_eq!(zeros.raw, "0000");
+
+    assert_eq!(
+        fixed_width::to_string(&trimmed).unwrap(),
+        "  abc 1200000012bar    x  "
+    );
+}
+
+#[derive(FixedWidth, Serialize)]
+struct Payment {
+    #[fixed_width(width = "8", overflow = "ellipsis")]
+    pub payee: String,
+    #[fixed_width(width = "4", justify = "right", pad_with = "0")]
+    pub amount: u32,
+    #[fixed_width(width = "4", overflow = "truncate_left")]
+    pub reference: String,
+}
+
+#[test]
+fn test_overflow() {
+    let payment = Payment {
+        payee: "Rosalind Franklin".to_string(),
+        amount: 250,
+        reference: "INV-2024-0042".to_string(),
+    };
+    assert_eq!(
+        fixed_width::to_string(&payment).unwrap(),
+        "Rosal...02500042"
+    );
+
+    let payment = Payment {
+        amount: 12500,
+        ..payment
+    };
+    assert!(fixed_width::to_string(&payment).is_err());
+}
+
+#[derive(FixedWidth, Serialize, Deserialize)]
+struct Dotted {
+    #[fixed_width(width = "9", pad_with = "·")]
+    pub label: String,
+    #[fixed_width(width = "4", pad_with = "0", justify = "right")]
+    pub page: u32,
+}
+
+#[test]
+fn test_multi_byte_pad() {
+    let dotted = Dotted {
+        label: "Intro".to_string(),
+        page: 7,
+    };
+    let s = fixed_width::to_string(&dotted).unwrap();
+    assert_eq!(s, "Intro··0007");
+
+    let read: Dotted = fixed_width::from_str(&s).unwrap();
+    assert_eq!(read.label, "Intro");
+    assert_eq!(read.page, 7);
+
+    let dotted = Dotted {
+        label: "Contents".to_string(),
+        ..dotted
+    };
+    assert!(fixed_width::to_string(&dotted).is_err());
+}
+
+enum StateOrName {
+    State(Record1),
+    Name(Record2),
+}
+
+#[test]
+fn test_record_types() {
+    let types = fixed_width::RecordTypes::new(0..1)
+        .record("0", StateOrName::State)
+        .record("1", StateOrName::Name);
+
+    let mut reader = Reader::from_string("0OHIO1 BOB2 ZED").width(5);
+    let mut records = reader.records(&types);
+
+    match records.next() {
+        Some(Ok(StateOrName::State(Record1 { state, .. }))) => assert_eq!(state, "OHIO"),
+        _ => panic!("expected a state record"),
+    }
+    match records.next() {
+        Some(Ok(StateOrName::Name(Record2 { name, .. }))) => assert_eq!(name, " BOB"),
+        _ => panic!("expected a name record"),
+    }
+    match records.next() {
+        Some(Err(fixed_width::Error::RecordError(2, 10, e))) => assert!(matches!(
+            *e,
+            fixed_width::Error::DeserializeError(DeserializeError::UnknownRecordType(_))
+        )),
+        _ => panic!("expected an unknown record type"),
+    }
+    assert!(records.next().is_none());
+}
+
+#[derive(FixedWidth, Serialize, Deserialize, Debug, PartialEq)]
+struct Total {
+    #[fixed_width(range = "1..9", pad_with = "0", justify = "right")]
+    pub amount: u64,
+}
+
+#[derive(FixedWidth, Serialize, Deserialize, Debug, PartialEq)]
+#[fixed_width(tag = "0..1", strict)]
+enum FileRecord {
+    #[fixed_width(tag_value = "H")]
+    Header {
+        #[fixed_width(width = "8")]
+        date: String,
+    },
+    #[fixed_width(tag_value = "D")]
+    Detail {
+        #[fixed_width(width = "4")]
+        name: String,
+        #[fixed_width(width = "4", pad_with = "0", justify = "right")]
+        amount: u32,
+    },
+    #[fixed_width(tag_value = "S")]
+    Total(Total),
+    #[fixed_width(tag_value = "T")]
+    Trailer,
+}
+
+#[test]
+fn test_tagged_enum() {
+    let records = vec![
+        FileRecord::Header {
+            date: "20240131".to_string(),
+        },
+        FileRecord::Detail {
+            name: "Ann".to_string(),
+            amount: 12,
+        },
+        FileRecord::Total(Total { amount: 42 }),
+        FileRecord::Trailer,
+    ];
+
+    let written: Vec<String> = records
+        .iter()
+        .map(|r| fixed_width::to_string(r).unwrap())
+        .collect();
+    assert_eq!(written, vec!["H20240131", "DAnn 0012", "S00000042", "T"]);
+
+    let read: Vec<FileRecord> = written
+        .iter()
+        .map(|s| fixed_width::from_str(s).unwrap())
+        .collect();
+    assert_eq!(read, records);
+
+    match fixed_width::from_str::<FileRecord>("X00000000") {
+        Err(fixed_width::Error::DeserializeError(e)) => assert!(matches!(
+            e.without_context(),
+            DeserializeError::UnknownRecordType(_)
+        )),
+        _ => panic!("expected an unknown record type"),
+    }
+}